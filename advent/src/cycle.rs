@@ -0,0 +1,69 @@
+// Brent's cycle detection for a deterministic `step`. Returns `(mu, lambda)`: the state after `mu`
+// steps is the first one that repeats, and it comes back every `lambda` steps. The simulation has
+// to be infinite, so a run that can end should step into a state that maps to itself.
+//
+// Only keeps two states around, at the cost of running the simulation about three times over.
+pub fn detect<S: Clone + Eq>(initial: S, mut step: impl FnMut(&S) -> S) -> (usize, usize) {
+    let mut power = 1;
+    let mut lambda = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == lambda {
+            tortoise = hare.clone();
+            power *= 2;
+            lambda = 0;
+        }
+        hare = step(&hare);
+        lambda += 1;
+    }
+
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..lambda {
+        hare = step(&hare);
+    }
+
+    let mut mu = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        mu += 1;
+    }
+
+    (mu, lambda)
+}
+
+// The state after `n` steps, without running all of them once the cycle is known.
+pub fn state_at<S: Clone + Eq>(initial: S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+    let (mu, lambda) = detect(initial.clone(), &mut step);
+    let steps = if n < mu { n } else { mu + (n - mu) % lambda };
+
+    (0..steps).fold(initial, |state, _| step(&state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        // 2, 4, 16, 256, 536, 296, ..., 136, 496, 16: the third state comes back 20 steps later.
+        assert_eq!(detect(2u64, |x| x * x % 1000), (2, 20));
+        assert_eq!(detect(0, |x| (x + 1) % 7), (0, 7));
+        assert_eq!(detect(5, |x| if *x > 0 { x - 1 } else { 0 }), (5, 1));
+    }
+
+    #[test]
+    fn test_state_at() {
+        let step = |x: &u64| x * x % 1000;
+        let simulated = (0..1_000).fold(2u64, |x, _| step(&x));
+
+        assert_eq!(state_at(2u64, step, 1_000), simulated);
+        assert_eq!(state_at(2u64, step, 1), 4);
+        assert_eq!(
+            state_at(2u64, step, 1_000_000_000_000),
+            state_at(2u64, step, 20)
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::{BTreeSet, HashMap};
+
+mod partial_order;
+pub use partial_order::{Cycle, PartialOrder};
+
+// Undirected graph over string node names. Names are interned, so nodes are handled internally
+// as `usize` ids in the order they were first seen.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Graph {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+    adjacency: Vec<BTreeSet<usize>>,
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_edges<'a>(edges: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let mut graph = Self::new();
+        for (a, b) in edges {
+            graph.add_edge(a, b);
+        }
+        graph
+    }
+
+    // Returns the id of `name`, adding it if it is new.
+    pub fn add_node(&mut self, name: &str) -> usize {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+
+        let id = self.names.len();
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        self.adjacency.push(BTreeSet::new());
+        id
+    }
+
+    pub fn add_edge(&mut self, a: &str, b: &str) {
+        let (a, b) = (self.add_node(a), self.add_node(b));
+        if a != b {
+            self.adjacency[a].insert(b);
+            self.adjacency[b].insert(a);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: usize) -> &str {
+        &self.names[id]
+    }
+
+    pub fn neighbors(&self, name: &str) -> impl Iterator<Item = &str> {
+        self.id(name)
+            .into_iter()
+            .flat_map(|id| self.adjacency[id].iter())
+            .map(|id| self.name(*id))
+    }
+
+    pub fn has_edge(&self, a: &str, b: &str) -> bool {
+        match (self.id(a), self.id(b)) {
+            (Some(a), Some(b)) => self.adjacency[a].contains(&b),
+            _ => false,
+        }
+    }
+
+    // Every triangle once, with its names sorted, and the list sorted too.
+    pub fn triangles(&self) -> Vec<[&str; 3]> {
+        let mut triangles = Vec::new();
+        for (a, neighbors) in self.adjacency.iter().enumerate() {
+            // Only look upwards from each node so each triangle is found from its smallest id.
+            for &b in neighbors.range(a + 1..) {
+                for &c in self.adjacency[b].range(b + 1..) {
+                    if neighbors.contains(&c) {
+                        let mut triangle = [self.name(a), self.name(b), self.name(c)];
+                        triangle.sort();
+                        triangles.push(triangle);
+                    }
+                }
+            }
+        }
+
+        triangles.sort();
+        triangles
+    }
+
+    // A largest clique, found with Bron–Kerbosch with pivoting, as sorted names. When several
+    // cliques tie, the one found first wins.
+    pub fn max_clique(&self) -> Vec<&str> {
+        let mut best = Vec::new();
+        self.bron_kerbosch(
+            &mut Vec::new(),
+            (0..self.len()).collect(),
+            BTreeSet::new(),
+            &mut best,
+        );
+
+        let mut names = best.into_iter().map(|id| self.name(id)).collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    // `clique` is the clique so far, `candidates` can extend it and `excluded` could too but have
+    // already been tried.
+    fn bron_kerbosch(
+        &self,
+        clique: &mut Vec<usize>,
+        mut candidates: BTreeSet<usize>,
+        mut excluded: BTreeSet<usize>,
+        best: &mut Vec<usize>,
+    ) {
+        if candidates.is_empty() {
+            if excluded.is_empty() && clique.len() > best.len() {
+                *best = clique.clone();
+            }
+            return;
+        }
+        // Even taking every candidate can't beat what we already have.
+        if clique.len() + candidates.len() <= best.len() {
+            return;
+        }
+
+        // Any maximal clique contains the pivot or one of its non-neighbours, so only those need
+        // to be tried. The pivot with the most candidate neighbours leaves the fewest.
+        let pivot = candidates
+            .union(&excluded)
+            .max_by_key(|u| self.adjacency[**u].intersection(&candidates).count())
+            .copied()
+            .unwrap();
+        let to_try = candidates
+            .difference(&self.adjacency[pivot])
+            .copied()
+            .collect::<Vec<_>>();
+
+        for v in to_try {
+            let neighbors = &self.adjacency[v];
+            clique.push(v);
+            self.bron_kerbosch(
+                clique,
+                candidates.intersection(neighbors).copied().collect(),
+                excluded.intersection(neighbors).copied().collect(),
+                best,
+            );
+            clique.pop();
+
+            candidates.remove(&v);
+            excluded.insert(v);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The LAN party example from 2024 day 23.
+    const EXAMPLE: &str = "\
+        kh-tc qp-kh de-cg ka-co yn-aq qp-ub cg-tb vc-aq tb-ka wh-tc yn-cg kh-ub ta-co de-co tc-td \
+        tb-wq wh-td ta-ka td-qp aq-cg wq-ub ub-vc de-ta wq-aq wq-vc wh-yn ka-de kh-ta co-tc wh-qp \
+        tb-vc td-yn";
+
+    fn example() -> Graph {
+        Graph::from_edges(
+            EXAMPLE
+                .split_whitespace()
+                .map(|edge| edge.split_once('-').unwrap()),
+        )
+    }
+
+    #[test]
+    fn test_graph() {
+        let graph = example();
+
+        assert_eq!(graph.len(), 16);
+        assert!(graph.has_edge("kh", "tc"));
+        assert!(graph.has_edge("tc", "kh"));
+        assert!(!graph.has_edge("kh", "co"));
+        assert!(!graph.has_edge("kh", "zz"));
+        assert_eq!(
+            graph.neighbors("kh").collect::<BTreeSet<_>>(),
+            BTreeSet::from(["qp", "tc", "ta", "ub"])
+        );
+    }
+
+    #[test]
+    fn test_triangles() {
+        let graph = example();
+        let triangles = graph.triangles();
+
+        assert_eq!(triangles.len(), 12);
+        assert_eq!(triangles[0], ["aq", "cg", "yn"]);
+        assert_eq!(
+            triangles
+                .iter()
+                .filter(|t| t.iter().any(|name| name.starts_with('t')))
+                .count(),
+            7
+        );
+    }
+
+    #[test]
+    fn test_max_clique() {
+        assert_eq!(example().max_clique(), vec!["co", "de", "ka", "ta"]);
+        assert!(Graph::new().max_clique().is_empty());
+    }
+}
@@ -0,0 +1,191 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    error::Error,
+    fmt::{self, Debug, Display},
+    hash::Hash,
+};
+
+// Pairwise "a comes before b" rules. The rules are deliberately not closed transitively: a full
+// rule set is allowed to be cyclic as long as its restriction to each subset that gets sorted
+// is not.
+#[derive(Debug, Clone)]
+pub struct PartialOrder<T> {
+    // Everything each item has to come before.
+    successors: HashMap<T, HashSet<T>>,
+}
+
+// Items whose rules contradict each other, in rule order: each one must come before the next and
+// the last before the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<T> {
+    pub members: Vec<T>,
+}
+
+impl<T: Debug> Display for Cycle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Ordering rules form a cycle:")?;
+        for member in &self.members {
+            write!(f, " {:?} ->", member)?;
+        }
+        match self.members.first() {
+            Some(first) => write!(f, " {:?}", first),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T: Debug> Error for Cycle<T> {}
+
+impl<T: Hash + Eq + Clone> PartialOrder<T> {
+    pub fn from_rules(rules: impl IntoIterator<Item = (T, T)>) -> Self {
+        let mut successors: HashMap<T, HashSet<T>> = HashMap::new();
+        for (a, b) in rules {
+            successors.entry(a).or_default().insert(b);
+        }
+
+        Self { successors }
+    }
+
+    pub fn precedes(&self, a: &T, b: &T) -> bool {
+        self.successors
+            .get(a)
+            .is_some_and(|successors| successors.contains(b))
+    }
+
+    // True if no item is placed after one it has to precede.
+    pub fn is_sorted(&self, items: &[T]) -> bool {
+        items.iter().enumerate().all(|(i, later)| {
+            items[..i]
+                .iter()
+                .all(|earlier| !self.precedes(later, earlier))
+        })
+    }
+
+    // Orders `items` so every rule between two of them holds. Whenever several items could go
+    // next, the one listed first in `items` wins, so the result doesn't depend on hashing.
+    pub fn sort(&self, items: &[T]) -> Result<Vec<T>, Cycle<T>> {
+        let mut blockers = items
+            .iter()
+            .map(|item| {
+                items
+                    .iter()
+                    .filter(|other| self.precedes(other, item))
+                    .count()
+            })
+            .collect::<Vec<_>>();
+        let mut ready = (0..items.len())
+            .filter(|i| blockers[*i] == 0)
+            .collect::<BTreeSet<_>>();
+
+        let mut sorted = Vec::with_capacity(items.len());
+        let mut placed = vec![false; items.len()];
+        while let Some(i) = ready.pop_first() {
+            sorted.push(items[i].clone());
+            placed[i] = true;
+            for (j, item) in items.iter().enumerate() {
+                if !placed[j] && self.precedes(&items[i], item) {
+                    blockers[j] -= 1;
+                    if blockers[j] == 0 {
+                        ready.insert(j);
+                    }
+                }
+            }
+        }
+
+        if sorted.len() < items.len() {
+            return Err(self.find_cycle(items, &placed));
+        }
+        Ok(sorted)
+    }
+
+    // Every item left unplaced still waits on another unplaced one, so walking backwards along
+    // rules from any of them has to come round to an item it already passed.
+    fn find_cycle(&self, items: &[T], placed: &[bool]) -> Cycle<T> {
+        let mut seen = vec![None; items.len()];
+        let mut walk = Vec::new();
+        let mut current = placed.iter().position(|p| !p).unwrap();
+
+        while seen[current].is_none() {
+            seen[current] = Some(walk.len());
+            walk.push(current);
+            current = (0..items.len())
+                .find(|j| !placed[*j] && self.precedes(&items[*j], &items[current]))
+                .unwrap();
+        }
+
+        let mut members = walk[seen[current].unwrap()..]
+            .iter()
+            .map(|i| items[*i].clone())
+            .collect::<Vec<_>>();
+        members.reverse();
+        Cycle { members }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The page ordering rules from 2024 day 5.
+    const RULES: &str = "\
+        47|53 97|13 97|61 97|47 75|29 61|13 75|53 29|13 97|29 53|29 61|53 97|53 61|29 47|13 \
+        75|47 97|75 47|61 75|61 47|29 75|13 53|13";
+
+    fn rules() -> PartialOrder<i64> {
+        PartialOrder::from_rules(RULES.split_whitespace().map(|rule| {
+            let (a, b) = rule.split_once('|').unwrap();
+            (a.parse().unwrap(), b.parse().unwrap())
+        }))
+    }
+
+    #[test]
+    fn test_is_sorted() {
+        let rules = rules();
+
+        assert!(rules.precedes(&47, &53));
+        assert!(!rules.precedes(&53, &47));
+        assert!(rules.is_sorted(&[75, 47, 61, 53, 29]));
+        assert!(rules.is_sorted(&[75, 29, 13]));
+        assert!(!rules.is_sorted(&[75, 97, 47, 61, 53]));
+        assert!(!rules.is_sorted(&[97, 13, 75, 29, 47]));
+    }
+
+    #[test]
+    fn test_sort() {
+        let rules = rules();
+
+        assert_eq!(
+            rules.sort(&[75, 97, 47, 61, 53]),
+            Ok(vec![97, 75, 47, 61, 53])
+        );
+        assert_eq!(rules.sort(&[61, 13, 29]), Ok(vec![61, 29, 13]));
+        assert_eq!(
+            rules.sort(&[97, 13, 75, 29, 47]),
+            Ok(vec![97, 75, 47, 29, 13])
+        );
+        // No rules between these, so they stay as they are.
+        assert_eq!(rules.sort(&[1, 3, 2]), Ok(vec![1, 3, 2]));
+    }
+
+    #[test]
+    fn test_cycle() {
+        let rules = PartialOrder::from_rules([(1, 2), (2, 3), (3, 1), (0, 1)]);
+
+        // Only a problem once all three are sorted together.
+        assert_eq!(rules.sort(&[3, 2]), Ok(vec![2, 3]));
+
+        let cycle = rules.sort(&[0, 1, 2, 3]).unwrap_err();
+        let mut members = cycle.members.clone();
+        members.sort();
+        assert_eq!(members, vec![1, 2, 3]);
+        let (a, b) = (cycle.members[0], cycle.members[1]);
+        assert!(rules.precedes(&a, &b));
+        assert_eq!(
+            cycle.to_string(),
+            format!(
+                "Ordering rules form a cycle: {} -> {} -> {} -> {}",
+                a, b, cycle.members[2], a
+            )
+        );
+    }
+}
@@ -0,0 +1,284 @@
+use std::ops::{Index, IndexMut};
+
+use crate::position::{Position_isize, Rect};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RawGrid<T>")
+)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    // Cells are stored row-major, so `cells[y * width + x]` is the cell at (x, y).
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            cells.len(),
+            width * height,
+            "Grid of {}x{} needs {} cells",
+            width,
+            height,
+            width * height
+        );
+
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn from_rows<R: IntoIterator<Item = T>>(rows: impl IntoIterator<Item = R>) -> Self {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+
+        for row in rows {
+            let before = cells.len();
+            cells.extend(row);
+            let row_width = cells.len() - before;
+            if *width.get_or_insert(row_width) != row_width {
+                panic!(
+                    "Row {} has width {} but expected {}",
+                    height,
+                    row_width,
+                    width.unwrap()
+                );
+            }
+            height += 1;
+        }
+
+        Self::new(width.unwrap_or(0), height, cells)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn bounds(&self) -> Rect<isize> {
+        Rect::from_size(self.width as isize, self.height as isize)
+    }
+
+    pub fn index_of(&self, pos: Position_isize) -> Option<usize> {
+        if !self.bounds().contains(pos) {
+            return None;
+        }
+
+        Some(pos.y as usize * self.width + pos.x as usize)
+    }
+
+    pub fn position_of(&self, index: usize) -> Position_isize {
+        Position_isize {
+            x: (index % self.width) as isize,
+            y: (index / self.width) as isize,
+        }
+    }
+
+    pub fn contains(&self, pos: Position_isize) -> bool {
+        self.index_of(pos).is_some()
+    }
+
+    pub fn get(&self, pos: Position_isize) -> Option<&T> {
+        self.cells.get(self.index_of(pos)?)
+    }
+
+    pub fn get_mut(&mut self, pos: Position_isize) -> Option<&mut T> {
+        let index = self.index_of(pos)?;
+        self.cells.get_mut(index)
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        if y >= self.height {
+            return None;
+        }
+
+        Some(&self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks_exact panics on a zero chunk size, which an empty grid would give us.
+        self.cells.chunks_exact(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> Option<impl Iterator<Item = &T>> {
+        if x >= self.width {
+            return None;
+        }
+
+        Some(self.cells.iter().skip(x).step_by(self.width))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.cells.iter().skip(x).step_by(self.width))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Position_isize> + '_ {
+        (0..self.cells.len()).map(|i| self.position_of(i))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Position_isize, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (self.position_of(i), c))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Position_isize, &mut T)> {
+        let width = self.width;
+        self.cells.iter_mut().enumerate().map(move |(i, c)| {
+            (
+                Position_isize {
+                    x: (i % width) as isize,
+                    y: (i / width) as isize,
+                },
+                c,
+            )
+        })
+    }
+
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    pub fn find(&self, value: &T) -> Option<Position_isize> {
+        self.cells
+            .iter()
+            .position(|c| c == value)
+            .map(|i| self.position_of(i))
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Self {
+        Self::new(width, height, vec![value; width * height])
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawGrid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+#[cfg(feature = "serde")]
+impl<T> TryFrom<RawGrid<T>> for Grid<T> {
+    type Error = String;
+
+    fn try_from(raw: RawGrid<T>) -> Result<Self, Self::Error> {
+        let size = raw
+            .width
+            .checked_mul(raw.height)
+            .ok_or_else(|| format!("Grid of {}x{} is too large", raw.width, raw.height))?;
+        if raw.cells.len() != size {
+            return Err(format!(
+                "Grid of {}x{} needs {} cells but has {}",
+                raw.width,
+                raw.height,
+                size,
+                raw.cells.len()
+            ));
+        }
+
+        Ok(Self::new(raw.width, raw.height, raw.cells))
+    }
+}
+
+impl<T> Index<Position_isize> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Position_isize) -> &Self::Output {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", pos))
+    }
+}
+
+impl<T> IndexMut<Position_isize> for Grid<T> {
+    fn index_mut(&mut self, pos: Position_isize) -> &mut Self::Output {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", pos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::Parser;
+
+    fn small() -> Grid<u8> {
+        Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]])
+    }
+
+    #[test]
+    fn test_get() {
+        let grid = small();
+
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid.get(Position_isize { x: 2, y: 1 }), Some(&6));
+        assert_eq!(grid.get(Position_isize { x: 3, y: 0 }), None);
+        assert_eq!(grid.get(Position_isize { x: -1, y: 0 }), None);
+        assert_eq!(grid[Position_isize { x: 0, y: 1 }], 4);
+        assert_eq!(grid.bounds().iter().count(), 6);
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = small();
+
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            vec![&[1, 2, 3][..], &[4, 5, 6][..]]
+        );
+        assert_eq!(
+            grid.column(1).unwrap().copied().collect::<Vec<_>>(),
+            vec![2, 5]
+        );
+        assert_eq!(
+            grid.columns()
+                .map(|c| c.copied().collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            vec![vec![1, 4], vec![2, 5], vec![3, 6]]
+        );
+    }
+
+    #[test]
+    fn test_map() {
+        let mut grid = small().map(|c| *c as i64 * 10);
+        grid[Position_isize { x: 1, y: 0 }] = -1;
+
+        assert_eq!(grid.cells(), &[10, -1, 30, 40, 50, 60]);
+    }
+
+    #[test]
+    fn test_as_grid() {
+        let parser = Parser::new(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/maze_test.txt"));
+        let grid = parser.as_grid();
+
+        assert_eq!(grid.width(), 27);
+        assert_eq!(grid.height(), 4);
+        assert_eq!(grid.find(&'S'), Some(Position_isize { x: 1, y: 2 }));
+        assert_eq!(grid.find(&'E'), Some(Position_isize { x: 24, y: 1 }));
+        assert_eq!(grid.cells().iter().filter(|c| **c == '#').count(), 67);
+    }
+}
@@ -1,2 +1,13 @@
+pub mod cycle;
+pub mod graph;
+pub mod grid;
+pub mod maze;
 pub mod parse;
 pub mod position;
+pub mod regions;
+pub mod render;
+pub mod search;
+pub mod union_find;
+
+#[cfg(feature = "serde")]
+mod serde_impls;
@@ -0,0 +1,67 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    parse::ParseError,
+    position::{Position_isize, Rect},
+};
+
+mod distance;
+mod junction;
+pub use distance::shortest_path_cells;
+pub use junction::{JunctionEdge, JunctionGraph};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Maze {
+    pub(crate) source: String,
+    // The input line that row 0 came from, for error locations.
+    pub(crate) first_line: usize,
+    pub width: usize,
+    pub height: usize,
+    pub walls: HashSet<Position_isize>,
+    pub special_chars: HashMap<char, HashSet<Position_isize>>,
+}
+
+impl Maze {
+    pub fn new(width: usize, height: usize, walls: HashSet<Position_isize>) -> Self {
+        Self {
+            source: "<maze>".to_string(),
+            first_line: 1,
+            width,
+            height,
+            walls,
+            special_chars: HashMap::new(),
+        }
+    }
+
+    pub fn bounds(&self) -> Rect<isize> {
+        Rect::from_size(self.width as isize, self.height as isize)
+    }
+
+    pub fn contains(&self, pos: Position_isize) -> bool {
+        self.bounds().contains(pos)
+    }
+
+    pub fn is_open(&self, pos: Position_isize) -> bool {
+        self.contains(pos) && !self.walls.contains(&pos)
+    }
+
+    pub fn get_only_position(&self, c: char) -> Result<Position_isize, ParseError> {
+        let missing = || ParseError::new(&self.source, format!("no {:?} in maze", c));
+        let positions = self.special_chars.get(&c).ok_or_else(missing)?;
+
+        let mut in_reading_order = positions.iter().copied().collect::<Vec<_>>();
+        in_reading_order.sort_by_key(|p| (p.y, p.x));
+        let first = *in_reading_order.first().ok_or_else(missing)?;
+        if let Some(second) = in_reading_order.get(1) {
+            return Err(ParseError {
+                source: self.source.clone(),
+                line: self.first_line + second.y as usize,
+                column: second.x as usize + 1,
+                text: c.to_string(),
+                message: format!("{:?} appears {} times in maze", c, positions.len()),
+            });
+        }
+
+        Ok(first)
+    }
+}
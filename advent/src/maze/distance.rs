@@ -0,0 +1,121 @@
+use std::{cmp::Reverse, collections::BinaryHeap, ops::Add};
+
+use num_traits::Zero;
+
+use super::Maze;
+use crate::{grid::Grid, position::Position_isize};
+
+impl Maze {
+    // Steps from the nearest of `from` to every open cell, or `None` where no source can reach.
+    pub fn distance_field(
+        &self,
+        from: impl IntoIterator<Item = Position_isize>,
+    ) -> Grid<Option<usize>> {
+        self.distance_field_with(from, |_, _| 1)
+    }
+
+    // Like `distance_field`, with `step_cost(from, to)` charged for each move between
+    // neighbouring open cells.
+    pub fn distance_field_with<C>(
+        &self,
+        from: impl IntoIterator<Item = Position_isize>,
+        mut step_cost: impl FnMut(Position_isize, Position_isize) -> C,
+    ) -> Grid<Option<C>>
+    where
+        C: Copy + Ord + Zero + Add<Output = C>,
+    {
+        let mut field = Grid::filled(self.width, self.height, None);
+
+        let mut to_process = BinaryHeap::new();
+        for pos in from {
+            if self.is_open(pos) {
+                to_process.push(Reverse((C::zero(), pos)));
+            }
+        }
+
+        while let Some(Reverse((dist, pos))) = to_process.pop() {
+            if field[pos].is_some() {
+                continue;
+            }
+            field[pos] = Some(dist);
+
+            for next in pos.neighbors4_in(self.bounds()) {
+                if self.is_open(next) && field[next].is_none() {
+                    to_process.push(Reverse((dist + step_cost(pos, next), next)));
+                }
+            }
+        }
+
+        field
+    }
+}
+
+// Cells that lie on some shortest path between the sources of `from_start` and `from_end`, in
+// reading order. `optimum` is normally `from_start` looked up at the end.
+pub fn shortest_path_cells<C>(
+    from_start: &Grid<Option<C>>,
+    from_end: &Grid<Option<C>>,
+    optimum: C,
+) -> Vec<Position_isize>
+where
+    C: Copy + Eq + Add<Output = C>,
+{
+    from_start
+        .iter()
+        .filter(|(pos, d_start)| match (d_start, from_end[*pos]) {
+            (Some(d_start), Some(d_end)) => *d_start + d_end == optimum,
+            _ => false,
+        })
+        .map(|(pos, _)| pos)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::Parser;
+
+    const MAZE: &str = "\
+#######
+#S...##
+#.#.#.#
+#...#E#
+###...#
+#######";
+
+    #[test]
+    fn test_distance_field() {
+        let maze = Parser::from_str(MAZE).as_maze();
+        let start = maze.get_only_position('S').unwrap();
+        let exit = maze.get_only_position('E').unwrap();
+
+        let from_start = maze.distance_field([start]);
+        assert_eq!(from_start[start], Some(0));
+        assert_eq!(from_start[exit], Some(8));
+        assert_eq!(from_start[Position_isize { x: 0, y: 0 }], None);
+        assert_eq!(from_start[Position_isize { x: 5, y: 1 }], None);
+
+        let from_end = maze.distance_field([exit]);
+        assert_eq!(
+            shortest_path_cells(&from_start, &from_end, from_start[exit].unwrap()).len(),
+            12
+        );
+    }
+
+    #[test]
+    fn test_multiple_sources_and_costs() {
+        let maze = Parser::from_str(MAZE).as_maze();
+        let start = maze.get_only_position('S').unwrap();
+        let exit = maze.get_only_position('E').unwrap();
+
+        let nearest = maze.distance_field([start, exit]);
+        assert_eq!(nearest[Position_isize { x: 3, y: 3 }], Some(4));
+        assert_eq!(nearest[Position_isize { x: 4, y: 4 }], Some(2));
+
+        // Moving down is free, everything else costs 10.
+        let weighted =
+            maze.distance_field_with([start], |from, to| if to.y > from.y { 0 } else { 10 });
+        assert_eq!(weighted[Position_isize { x: 1, y: 3 }], Some(0));
+        assert_eq!(weighted[exit], Some(50));
+    }
+}
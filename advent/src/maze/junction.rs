@@ -0,0 +1,192 @@
+use std::collections::{HashMap, HashSet};
+
+use super::Maze;
+use crate::position::{Direction, Position_isize};
+
+// One walk along a corridor. Edges are directed, so every corridor shows up once from each end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JunctionEdge {
+    pub from: Position_isize,
+    pub to: Position_isize,
+    // Direction of the step leaving `from`, and of the one arriving at `to`.
+    pub exit: Direction,
+    pub entry: Direction,
+    pub cost: usize,
+    // The corridor cells strictly between `from` and `to`, in walking order.
+    pub cells: Vec<Position_isize>,
+}
+
+impl JunctionEdge {
+    pub fn path(&self) -> impl Iterator<Item = Position_isize> + '_ {
+        std::iter::once(self.from)
+            .chain(self.cells.iter().copied())
+            .chain(std::iter::once(self.to))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JunctionGraph {
+    pub nodes: HashSet<Position_isize>,
+    pub edges: Vec<JunctionEdge>,
+    outgoing: HashMap<Position_isize, Vec<usize>>,
+}
+
+impl JunctionGraph {
+    pub fn edges_from(&self, pos: Position_isize) -> impl Iterator<Item = (usize, &JunctionEdge)> {
+        self.outgoing
+            .get(&pos)
+            .into_iter()
+            .flatten()
+            .map(|&i| (i, &self.edges[i]))
+    }
+
+    // Every maze cell covered by the given edges, endpoints included.
+    pub fn covered_cells(&self, edges: impl IntoIterator<Item = usize>) -> HashSet<Position_isize> {
+        edges
+            .into_iter()
+            .flat_map(|i| self.edges[i].path())
+            .collect()
+    }
+}
+
+impl Maze {
+    fn open_neighbors(
+        &self,
+        pos: Position_isize,
+    ) -> impl Iterator<Item = (Direction, Position_isize)> + '_ {
+        Direction::ALL4
+            .into_iter()
+            .map(move |d| (d, pos + d))
+            .filter(|(_, p)| self.is_open(*p))
+    }
+
+    // Collapses every 1-wide corridor into a single weighted edge. Nodes are junctions, dead ends
+    // and marker cells; a loop with none of those gets one of its cells promoted to a node.
+    pub fn to_junction_graph(&self) -> JunctionGraph {
+        let open = self
+            .bounds()
+            .iter()
+            .filter(|p| self.is_open(*p))
+            .collect::<Vec<_>>();
+
+        let markers = self
+            .special_chars
+            .values()
+            .flatten()
+            .copied()
+            .collect::<HashSet<_>>();
+
+        let mut graph = JunctionGraph {
+            nodes: open
+                .iter()
+                .copied()
+                .filter(|p| markers.contains(p) || self.open_neighbors(*p).count() != 2)
+                .collect(),
+            edges: Vec::new(),
+            outgoing: HashMap::new(),
+        };
+
+        let mut visited = graph.nodes.clone();
+        let mut pending = graph.nodes.iter().copied().collect::<Vec<_>>();
+        pending.sort_by_key(|p| (p.y, p.x));
+        let mut unvisited = open.iter();
+
+        loop {
+            for node in pending.drain(..) {
+                for (exit, _) in self.open_neighbors(node) {
+                    let edge = self.walk_corridor(&graph.nodes, node, exit);
+                    visited.extend(edge.cells.iter().copied());
+                    graph
+                        .outgoing
+                        .entry(node)
+                        .or_default()
+                        .push(graph.edges.len());
+                    graph.edges.push(edge);
+                }
+            }
+
+            match unvisited.find(|p| !visited.contains(p)) {
+                Some(&cell) => {
+                    graph.nodes.insert(cell);
+                    visited.insert(cell);
+                    pending.push(cell);
+                }
+                None => break,
+            }
+        }
+
+        graph
+    }
+
+    fn walk_corridor(
+        &self,
+        nodes: &HashSet<Position_isize>,
+        from: Position_isize,
+        exit: Direction,
+    ) -> JunctionEdge {
+        let mut cells = Vec::new();
+        let mut previous = from;
+        let mut current = from + exit;
+        let mut entry = exit;
+
+        while !nodes.contains(&current) {
+            cells.push(current);
+            let (direction, next) = self
+                .open_neighbors(current)
+                .find(|(_, p)| *p != previous)
+                .expect("Corridor cells have exactly two open neighbours");
+            entry = direction;
+            previous = current;
+            current = next;
+        }
+
+        JunctionEdge {
+            from,
+            to: current,
+            exit,
+            entry,
+            cost: cells.len() + 1,
+            cells,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::Parser;
+
+    #[test]
+    fn test_junction_graph() {
+        let maze = Parser::from_str("#######\n#S....#\n#.###.#\n#...#E#\n#######").as_maze();
+        let start = maze.get_only_position('S').unwrap();
+        let end = maze.get_only_position('E').unwrap();
+        let graph = maze.to_junction_graph();
+
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(graph.edges.len(), 4);
+
+        let (_, to_end) = graph.edges_from(start).find(|(_, e)| e.to == end).unwrap();
+        assert_eq!(to_end.cost, 6);
+        assert_eq!(to_end.exit, Direction::East);
+        assert_eq!(to_end.entry, Direction::South);
+        assert_eq!(to_end.cells.len(), 5);
+
+        let (_, back) = graph.edges_from(end).next().unwrap();
+        assert_eq!((back.to, back.cost), (start, 6));
+        assert_eq!(back.entry, Direction::West);
+
+        let all_cells = graph.covered_cells(0..graph.edges.len());
+        assert_eq!(all_cells.len(), 11);
+    }
+
+    #[test]
+    fn test_loop_without_junctions() {
+        let maze = Parser::from_str("#####\n#...#\n#.#.#\n#...#\n#####").as_maze();
+        let graph = maze.to_junction_graph();
+
+        assert_eq!(graph.nodes.len(), 1);
+        assert_eq!(graph.edges.len(), 2);
+        assert!(graph.edges.iter().all(|e| e.from == e.to && e.cost == 8));
+    }
+}
@@ -1,66 +1,464 @@
 use std::{
-    collections::{HashMap, HashSet},
+    fmt::{self, Display},
     fs::File,
-    io::{BufRead, BufReader},
+    io::{BufRead, BufReader, Read},
+    str::FromStr,
 };
 
-use crate::position::{Position, Position_isize};
+use crate::grid::Grid;
+
+mod maze;
+mod pattern;
+pub use crate::maze::Maze;
+pub use maze::MazeBuilder;
+pub use pattern::{FromCaptures, Pattern};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub source: String,
+    // Line and column are 1-based. A line of 0 means the error is about the input as a whole.
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(source: &str, message: impl Into<String>) -> Self {
+        Self {
+            source: source.to_string(),
+            line: 0,
+            column: 0,
+            text: String::new(),
+            message: message.into(),
+        }
+    }
+
+    fn io(source: &str, error: std::io::Error) -> Self {
+        Self::new(source, error.to_string())
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            return write!(f, "{}: {}", self.source, self.message);
+        }
+
+        write!(
+            f,
+            "{}:{}:{}: {} (found {:?})",
+            self.source, self.line, self.column, self.message, self.text
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Line<'a> {
+    source: &'a str,
+    number: usize,
+    text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    // The 1-based column `field` starts at when it is a slice of this line, otherwise 1.
+    pub fn column_of(&self, field: &str) -> usize {
+        let start = self.text.as_ptr() as usize;
+        let offset = (field.as_ptr() as usize).wrapping_sub(start);
+        if offset > self.text.len() {
+            return 1;
+        }
+
+        self.text[..offset].chars().count() + 1
+    }
+
+    pub fn error(&self, field: &str, message: impl Into<String>) -> ParseError {
+        ParseError {
+            source: self.source.to_string(),
+            line: self.number,
+            column: self.column_of(field),
+            text: field.to_string(),
+            message: message.into(),
+        }
+    }
+
+    pub fn parse<T: FromStr>(&self, field: &str) -> Result<T, ParseError>
+    where
+        T::Err: Display,
+    {
+        let field = field.trim();
+        field.parse::<T>().map_err(|e| {
+            self.error(
+                field,
+                format!("invalid {}: {}", std::any::type_name::<T>(), e),
+            )
+        })
+    }
+
+    pub fn split_once(&self, delimiter: &str) -> Result<(&'a str, &'a str), ParseError> {
+        self.text
+            .split_once(delimiter)
+            .ok_or_else(|| self.error(self.text, format!("missing {:?}", delimiter)))
+    }
+
+    pub fn fields<T: FromStr>(&self, separator: &str) -> Result<Vec<T>, ParseError>
+    where
+        T::Err: Display,
+    {
+        self.text
+            .split(separator)
+            .map(|field| self.parse(field))
+            .collect()
+    }
+
+    pub fn whitespace_fields<T: FromStr>(&self) -> Result<Vec<T>, ParseError>
+    where
+        T::Err: Display,
+    {
+        self.text
+            .split_ascii_whitespace()
+            .map(|field| self.parse(field))
+            .collect()
+    }
+
+    // Every integer in the line, in order. A `-` is a sign when it directly precedes a digit
+    // and does not follow one, so `v=3,-3` gives 3 and -3 but `10-20` gives 10 and 20.
+    pub fn ints<T: FromStr>(&self) -> Result<Vec<T>, ParseError>
+    where
+        T::Err: Display,
+    {
+        let bytes = self.text.as_bytes();
+        let mut values = Vec::new();
+        let mut i = 0;
+
+        while i < bytes.len() {
+            let is_sign = bytes[i] == b'-'
+                && bytes.get(i + 1).is_some_and(|b| b.is_ascii_digit())
+                && (i == 0 || !bytes[i - 1].is_ascii_digit());
+            if !is_sign && !bytes[i].is_ascii_digit() {
+                i += 1;
+                continue;
+            }
+
+            let start = i;
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            values.push(self.parse(&self.text[start..i])?);
+        }
+
+        Ok(values)
+    }
+
+    pub fn digits(&self) -> Result<Vec<u8>, ParseError> {
+        self.text
+            .char_indices()
+            .map(|(i, c)| {
+                c.to_digit(10)
+                    .map(|d| d as u8)
+                    .ok_or_else(|| self.error(&self.text[i..i + c.len_utf8()], "expected a digit"))
+            })
+            .collect()
+    }
+}
+
+// Passing this as the input file reads the input from stdin instead.
+pub const STDIN: &str = "-";
+
+enum Input {
+    File,
+    Lines(Vec<String>),
+}
 
 pub struct Parser {
-    input_file: String,
+    source: String,
+    input: Input,
 }
 
 impl Parser {
     pub fn new(input_file: &str) -> Self {
+        if input_file == STDIN {
+            return Self::try_new(input_file).unwrap_or_else(|e| panic!("{}", e));
+        }
+
+        Self {
+            source: input_file.to_string(),
+            input: Input::File,
+        }
+    }
+
+    pub fn try_new(input_file: &str) -> Result<Self, ParseError> {
+        if input_file == STDIN {
+            return Self::from_named_reader("<stdin>", std::io::stdin().lock());
+        }
+
+        File::open(input_file).map_err(|e| ParseError::io(input_file, e))?;
+
+        Ok(Self::new(input_file))
+    }
+
+    // For day binaries: runs `parse` over `input_file`, or prints the error and exits.
+    pub fn parse_or_exit<T>(
+        input_file: &str,
+        parse: impl FnOnce(&Parser) -> Result<T, ParseError>,
+    ) -> T {
+        match Self::try_new(input_file).and_then(|parser| parse(&parser)) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(text: &str) -> Self {
         Self {
-            input_file: input_file.to_string(),
+            source: "<input>".to_string(),
+            input: Input::Lines(text.lines().map(|line| line.to_string()).collect()),
         }
     }
 
+    pub fn from_reader(reader: impl Read) -> Result<Self, ParseError> {
+        Self::from_named_reader("<input>", reader)
+    }
+
+    fn from_named_reader(source: &str, reader: impl Read) -> Result<Self, ParseError> {
+        Ok(Self {
+            source: source.to_string(),
+            input: Input::Lines(read_lines(source, reader)?),
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
     pub fn get_lines(&self) -> Vec<String> {
-        let file = File::open(&self.input_file).expect("Failed to open file");
-        let reader = BufReader::new(file);
-        reader
-            .lines()
-            .map(|line| line.expect("Failed to read line"))
-            .collect()
+        self.try_get_lines().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_get_lines(&self) -> Result<Vec<String>, ParseError> {
+        match &self.input {
+            Input::File => read_lines(
+                &self.source,
+                File::open(&self.source).map_err(|e| ParseError::io(&self.source, e))?,
+            ),
+            Input::Lines(lines) => Ok(lines.clone()),
+        }
+    }
+
+    pub fn try_parse_lines<T>(
+        &self,
+        f: impl FnMut(&Line) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        parse_lines(&self.source, 1, &self.try_get_lines()?, f)
+    }
+
+    pub fn ints<T: FromStr>(&self) -> Vec<Vec<T>>
+    where
+        T::Err: Display,
+    {
+        self.try_ints().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_ints<T: FromStr>(&self) -> Result<Vec<Vec<T>>, ParseError>
+    where
+        T::Err: Display,
+    {
+        self.try_parse_lines(|line| line.ints())
+    }
+
+    pub fn digits(&self) -> Vec<Vec<u8>> {
+        self.try_digits().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_digits(&self) -> Result<Vec<Vec<u8>>, ParseError> {
+        self.try_parse_lines(|line| line.digits())
+    }
+
+    pub fn as_grid(&self) -> Grid<char> {
+        self.try_as_grid().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_as_grid(&self) -> Result<Grid<char>, ParseError> {
+        grid_from_lines(&self.source, 1, &self.try_get_lines()?)
+    }
+
+    // Splits the input into blocks of lines separated by one or more blank lines.
+    pub fn sections(&self) -> impl Iterator<Item = Section> {
+        self.try_sections().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_sections(&self) -> Result<impl Iterator<Item = Section>, ParseError> {
+        let mut sections = Vec::new();
+        let mut current: Option<Section> = None;
+
+        for (i, line) in self.try_get_lines()?.into_iter().enumerate() {
+            if line.trim().is_empty() {
+                sections.extend(current.take());
+                continue;
+            }
+
+            current
+                .get_or_insert_with(|| Section {
+                    source: self.source.clone(),
+                    first_line: i + 1,
+                    lines: Vec::new(),
+                })
+                .lines
+                .push(line);
+        }
+        sections.extend(current);
+
+        Ok(sections.into_iter())
     }
 
     pub fn as_maze(&self) -> Maze {
-        let lines = self.get_lines();
-        let mut results = HashMap::new();
-
-        lines.iter().enumerate().for_each(|(y, line)| {
-            line.char_indices().for_each(|(x, c)| {
-                let positions = results.entry(c).or_insert(HashSet::new());
-                positions.insert(Position_isize {
-                    x: x as isize,
-                    y: y as isize,
-                });
-            });
-        });
-
-        Maze {
-            walls: results.remove(&'#').unwrap(),
-            special_chars: results,
+        self.try_as_maze().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_as_maze(&self) -> Result<Maze, ParseError> {
+        self.try_as_maze_with(&MazeBuilder::default())
+    }
+
+    pub fn as_maze_with(&self, builder: &MazeBuilder) -> Maze {
+        self.try_as_maze_with(builder)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_as_maze_with(&self, builder: &MazeBuilder) -> Result<Maze, ParseError> {
+        let lines = self.try_get_lines()?;
+        builder.build(&self.source, 1, lines_of(&self.source, 1, &lines))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    source: String,
+    first_line: usize,
+    lines: Vec<String>,
+}
+
+impl Section {
+    pub fn first_line(&self) -> usize {
+        self.first_line
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = Line<'_>> {
+        lines_of(&self.source, self.first_line, &self.lines)
+    }
+
+    pub fn parse_lines<T>(
+        &self,
+        f: impl FnMut(&Line) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        parse_lines(&self.source, self.first_line, &self.lines, f)
+    }
+
+    pub fn as_grid(&self) -> Result<Grid<char>, ParseError> {
+        grid_from_lines(&self.source, self.first_line, &self.lines)
+    }
+
+    pub fn as_maze_with(&self, builder: &MazeBuilder) -> Result<Maze, ParseError> {
+        builder.build(&self.source, self.first_line, self.lines())
+    }
+
+    pub fn single_line(&self) -> Result<Line<'_>, ParseError> {
+        let mut lines = self.lines();
+        let first = lines.next().ok_or_else(|| {
+            ParseError::new(&self.source, "expected a section with a single line")
+        })?;
+        if let Some(extra) = lines.next() {
+            return Err(extra.error(extra.text, "expected a section with a single line"));
         }
+
+        Ok(first)
     }
 }
 
-pub struct Maze {
-    pub walls: HashSet<Position_isize>,
-    pub special_chars: HashMap<char, HashSet<Position_isize>>,
+fn read_lines(source: &str, reader: impl Read) -> Result<Vec<String>, ParseError> {
+    BufReader::new(reader)
+        .lines()
+        .map(|line| line.map_err(|e| ParseError::io(source, e)))
+        .collect()
 }
 
-impl Maze {
-    pub fn get_only_position(&self, c: char) -> Option<Position<isize>> {
-        self.special_chars.get(&c)?.iter().next().copied()
+fn lines_of<'a>(
+    source: &'a str,
+    first_line: usize,
+    lines: &'a [String],
+) -> impl Iterator<Item = Line<'a>> {
+    lines.iter().enumerate().map(move |(i, text)| Line {
+        source,
+        number: first_line + i,
+        text,
+    })
+}
+
+fn parse_lines<T>(
+    source: &str,
+    first_line: usize,
+    lines: &[String],
+    mut f: impl FnMut(&Line) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    lines_of(source, first_line, lines)
+        .map(|line| f(&line))
+        .collect()
+}
+
+fn grid_from_lines(
+    source: &str,
+    first_line: usize,
+    lines: &[String],
+) -> Result<Grid<char>, ParseError> {
+    let width = lines.first().map(|l| l.chars().count()).unwrap_or(0);
+    if let Some((i, text)) = lines
+        .iter()
+        .enumerate()
+        .find(|(_, l)| l.chars().count() != width)
+    {
+        let line = Line {
+            source,
+            number: first_line + i,
+            text,
+        };
+        return Err(line.error(
+            text,
+            format!(
+                "expected {} columns but found {}",
+                width,
+                text.chars().count()
+            ),
+        ));
     }
+
+    Ok(Grid::from_rows(lines.iter().map(|line| line.chars())))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::position::{Position, Position_isize};
 
     #[test]
     fn test_part1() {
@@ -77,4 +475,143 @@ mod tests {
             Position::<isize> { x: 24, y: 1 }
         );
     }
+
+    #[test]
+    fn test_field_errors() {
+        let line = Line {
+            source: "input.txt",
+            number: 3,
+            text: "190: 10 1x9",
+        };
+
+        let (total, values) = line.split_once(": ").unwrap();
+        assert_eq!(line.parse::<i64>(total), Ok(190));
+
+        let error = Line {
+            text: values,
+            ..line
+        }
+        .whitespace_fields::<i64>()
+        .unwrap_err();
+        assert_eq!(error.line, 3);
+        assert_eq!(error.text, "1x9");
+        assert_eq!(
+            line.error(&line.text[8..], "bad").column,
+            9,
+            "columns are 1-based"
+        );
+
+        let error = line.fields::<i64>(" ").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (1, "190:"));
+        assert!(error.to_string().starts_with("input.txt:3:1: invalid i64"));
+    }
+
+    #[test]
+    fn test_try_parse_lines() {
+        let path = env!("CARGO_MANIFEST_DIR").to_owned() + "/src/maze_test.txt";
+        let error = Parser::try_new(&path)
+            .unwrap()
+            .try_parse_lines(|line| line.fields::<i64>(","))
+            .unwrap_err();
+
+        assert_eq!((error.line, error.column), (1, 1));
+        assert_eq!(error.source, path);
+
+        let error = Parser::try_new("does/not/exist.txt").err().unwrap();
+        assert_eq!(error.line, 0);
+        assert!(error.to_string().starts_with("does/not/exist.txt: "));
+    }
+
+    #[test]
+    fn test_sections() {
+        let parser =
+            Parser::new(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/sections_test.txt"));
+        let sections = parser.sections().collect::<Vec<_>>();
+
+        assert_eq!(sections.len(), 4);
+        assert_eq!(
+            sections.iter().map(|s| s.first_line()).collect::<Vec<_>>(),
+            vec![1, 4, 7, 11]
+        );
+
+        let rules = sections[0]
+            .parse_lines(|line| {
+                let (a, b) = line.split_once("|")?;
+                Ok((line.parse::<i64>(a)?, line.parse::<i64>(b)?))
+            })
+            .unwrap();
+        assert_eq!(rules, vec![(47, 53), (97, 13)]);
+
+        let program = sections[1].single_line().unwrap();
+        assert_eq!(program.fields::<i64>(","), Ok(vec![0, 1, 5, 4, 3, 0]));
+
+        let grid = sections[2].as_grid().unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert_eq!(grid.find(&'S'), Some(Position_isize { x: 1, y: 1 }));
+
+        let error = sections[3].as_grid().unwrap_err();
+        assert_eq!((error.line, error.column), (12, 1));
+        assert_eq!(sections[3].single_line().unwrap_err().line, 12);
+    }
+
+    #[test]
+    fn test_in_memory_sources() {
+        let text = "###\n#S#\n#E#\n###\n";
+        let from_file =
+            Parser::new(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/maze_test.txt"));
+
+        for parser in [
+            Parser::from_str(text),
+            Parser::from_reader(text.as_bytes()).unwrap(),
+        ] {
+            assert_eq!(parser.source(), "<input>");
+            assert_eq!(parser.get_lines().len(), 4);
+            assert_eq!(parser.as_grid().height(), 4);
+
+            let maze = parser.as_maze();
+            assert_eq!(maze.walls.len(), 10);
+            assert_eq!(
+                maze.get_only_position('E'),
+                Ok(Position_isize { x: 1, y: 2 })
+            );
+        }
+
+        assert_eq!(
+            Parser::from_str(&from_file.get_lines().join("\n")).get_lines(),
+            from_file.get_lines()
+        );
+    }
+
+    #[test]
+    fn test_ints() {
+        let parser = Parser::from_str(
+            "p=0,4 v=3,-3\nButton A: X+94, Y+34\n190: 10 19\nProgram: 0,1,5,4,3,0\n10-20 --7 x-1\n",
+        );
+
+        assert_eq!(
+            parser.ints::<i64>(),
+            vec![
+                vec![0, 4, 3, -3],
+                vec![94, 34],
+                vec![190, 10, 19],
+                vec![0, 1, 5, 4, 3, 0],
+                vec![10, 20, -7, -1],
+            ]
+        );
+
+        let error = parser.try_ints::<u32>().unwrap_err();
+        assert_eq!((error.line, error.column), (1, 11));
+        assert_eq!(error.text, "-3");
+    }
+
+    #[test]
+    fn test_digits() {
+        assert_eq!(
+            Parser::from_str("2333133121\n0123").digits(),
+            vec![vec![2, 3, 3, 3, 1, 3, 3, 1, 2, 1], vec![0, 1, 2, 3]]
+        );
+
+        let error = Parser::from_str("01\n9.8").try_digits().unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+    }
 }
@@ -0,0 +1,157 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{Line, ParseError};
+use crate::{maze::Maze, position::Position_isize};
+
+// Decides what each character of a maze becomes. Blocking characters end up in `Maze::walls`,
+// ignored characters are open floor that is not recorded anywhere, and markers are collected
+// into `Maze::special_chars`. When no markers are given, every other character is a marker;
+// otherwise any character outside the three sets is an error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MazeBuilder {
+    walls: HashSet<char>,
+    ignored: HashSet<char>,
+    markers: Option<HashSet<char>>,
+}
+
+impl Default for MazeBuilder {
+    fn default() -> Self {
+        Self {
+            walls: HashSet::from(['#']),
+            ignored: HashSet::from(['.']),
+            markers: None,
+        }
+    }
+}
+
+impl MazeBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn walls(mut self, chars: &str) -> Self {
+        self.walls = chars.chars().collect();
+        self
+    }
+
+    pub fn ignore(mut self, chars: &str) -> Self {
+        self.ignored = chars.chars().collect();
+        self
+    }
+
+    pub fn markers(mut self, chars: &str) -> Self {
+        self.markers = Some(chars.chars().collect());
+        self
+    }
+
+    pub(super) fn build<'a>(
+        &self,
+        source: &str,
+        first_line: usize,
+        lines: impl Iterator<Item = Line<'a>>,
+    ) -> Result<Maze, ParseError> {
+        let mut maze = Maze {
+            source: source.to_string(),
+            first_line,
+            width: 0,
+            height: 0,
+            walls: HashSet::new(),
+            special_chars: HashMap::new(),
+        };
+
+        for (y, line) in lines.enumerate() {
+            maze.height = y + 1;
+            for (x, (i, c)) in line.text.char_indices().enumerate() {
+                maze.width = maze.width.max(x + 1);
+                let pos = Position_isize {
+                    x: x as isize,
+                    y: y as isize,
+                };
+
+                if self.walls.contains(&c) {
+                    maze.walls.insert(pos);
+                } else if self.ignored.contains(&c) {
+                    continue;
+                } else if self.markers.as_ref().is_none_or(|m| m.contains(&c)) {
+                    maze.special_chars.entry(c).or_default().insert(pos);
+                } else {
+                    return Err(line.error(
+                        &line.text[i..i + c.len_utf8()],
+                        format!("unexpected {:?} in maze", c),
+                    ));
+                }
+            }
+        }
+
+        Ok(maze)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::Parser;
+
+    #[test]
+    fn test_builder() {
+        let parser = Parser::from_str("....#.....\n.........#\n..#.......\n....^.....\n");
+
+        let maze = parser.as_maze();
+        assert_eq!((maze.width, maze.height), (10, 4));
+        assert_eq!(maze.walls.len(), 3);
+        assert_eq!(maze.special_chars.len(), 1);
+        assert_eq!(
+            maze.get_only_position('^'),
+            Ok(Position_isize { x: 4, y: 3 })
+        );
+        assert!(maze.is_open(Position_isize { x: 0, y: 0 }));
+        assert!(!maze.is_open(Position_isize { x: 4, y: 0 }));
+        assert!(!maze.is_open(Position_isize { x: 10, y: 0 }));
+
+        let error = parser
+            .try_as_maze_with(&MazeBuilder::new().markers("<>v"))
+            .unwrap_err();
+        assert_eq!((error.line, error.column), (4, 5));
+
+        let mut maze = Parser::from_str("##########\n#..O..O.#\n#.@O....#\n##########")
+            .as_maze_with(&MazeBuilder::new().markers("O@"));
+        assert_eq!(maze.special_chars[&'O'].len(), 3);
+        let error = maze.get_only_position('O').unwrap_err();
+        assert_eq!((error.line, error.column), (2, 7));
+        assert!(maze.get_only_position('^').is_err());
+
+        maze.special_chars.insert('^', HashSet::new());
+        assert!(maze.get_only_position('^').is_err());
+    }
+
+    #[test]
+    fn test_section_positions() {
+        let mut sections = Parser::from_str("1\n2\n\n#S#\n.S.\n")
+            .try_sections()
+            .unwrap();
+        sections.next();
+        let maze = sections
+            .next()
+            .unwrap()
+            .as_maze_with(&MazeBuilder::new())
+            .unwrap();
+
+        let error = maze.get_only_position('S').unwrap_err();
+        assert_eq!((error.line, error.column), (5, 2));
+        assert_eq!(
+            error.to_string(),
+            "<input>:5:2: 'S' appears 2 times in maze (found \"S\")"
+        );
+    }
+
+    #[test]
+    fn test_no_walls() {
+        let maze = Parser::from_str("S..\n..E").as_maze();
+
+        assert!(maze.walls.is_empty());
+        assert_eq!(
+            maze.get_only_position('E'),
+            Ok(Position_isize { x: 2, y: 1 })
+        );
+    }
+}
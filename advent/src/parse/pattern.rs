@@ -0,0 +1,189 @@
+use std::{fmt::Display, str::FromStr};
+
+use super::{Line, ParseError};
+
+// A line pattern where every `{}` is a capture and everything else must match literally,
+// e.g. `Pattern::new("p={},{} v={},{}")`. A capture runs up to the next occurrence of the
+// literal that follows it, or to the end of the line for a trailing capture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    literals: Vec<String>,
+}
+
+#[macro_export]
+macro_rules! pattern {
+    ($pattern:literal) => {{
+        static PATTERN: std::sync::OnceLock<$crate::parse::Pattern> = std::sync::OnceLock::new();
+        PATTERN.get_or_init(|| $crate::parse::Pattern::new($pattern))
+    }};
+}
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Self {
+        let literals = pattern
+            .split("{}")
+            .map(|literal| literal.to_string())
+            .collect::<Vec<_>>();
+        if literals[1..literals.len().max(2) - 1]
+            .iter()
+            .any(|l| l.is_empty())
+        {
+            panic!(
+                "Pattern {:?} has two captures with nothing between them",
+                pattern
+            );
+        }
+
+        Self { literals }
+    }
+
+    pub fn captures_len(&self) -> usize {
+        self.literals.len() - 1
+    }
+
+    pub fn captures<'a>(&self, line: &Line<'a>) -> Result<Vec<&'a str>, ParseError> {
+        let expected =
+            |rest: &str, literal: &str| line.error(rest, format!("expected {:?}", literal));
+
+        let (first, others) = self.literals.split_first().unwrap();
+        let mut rest = line
+            .text
+            .strip_prefix(first.as_str())
+            .ok_or_else(|| expected(line.text, first))?;
+
+        let mut captures = Vec::with_capacity(others.len());
+        for literal in others {
+            if literal.is_empty() {
+                // Only the final literal can be empty, so the capture takes the rest of the line.
+                captures.push(rest);
+                rest = &rest[rest.len()..];
+                continue;
+            }
+
+            let end = rest
+                .find(literal.as_str())
+                .ok_or_else(|| expected(rest, literal))?;
+            captures.push(&rest[..end]);
+            rest = &rest[end + literal.len()..];
+        }
+
+        if !rest.is_empty() {
+            return Err(line.error(rest, "unexpected trailing text"));
+        }
+
+        Ok(captures)
+    }
+
+    pub fn parse<T: FromCaptures>(&self, line: &Line) -> Result<T, ParseError> {
+        assert_eq!(
+            self.captures_len(),
+            T::LEN,
+            "Pattern {:?} has {} captures but the result has {} fields",
+            self.literals.join("{}"),
+            self.captures_len(),
+            T::LEN
+        );
+
+        T::from_captures(line, &self.captures(line)?)
+    }
+}
+
+pub trait FromCaptures: Sized {
+    const LEN: usize;
+
+    fn from_captures(line: &Line, captures: &[&str]) -> Result<Self, ParseError>;
+}
+
+macro_rules! impl_from_captures {
+    ($len:literal: $($name:ident $index:tt),+) => {
+        impl<$($name: FromStr),+> FromCaptures for ($($name,)+)
+        where
+            $($name::Err: Display),+
+        {
+            const LEN: usize = $len;
+
+            fn from_captures(line: &Line, captures: &[&str]) -> Result<Self, ParseError> {
+                Ok(($(line.parse::<$name>(captures[$index])?,)+))
+            }
+        }
+    };
+}
+
+impl_from_captures!(1: A 0);
+impl_from_captures!(2: A 0, B 1);
+impl_from_captures!(3: A 0, B 1, C 2);
+impl_from_captures!(4: A 0, B 1, C 2, D 3);
+impl_from_captures!(5: A 0, B 1, C 2, D 3, E 4);
+impl_from_captures!(6: A 0, B 1, C 2, D 3, E 4, F 5);
+impl_from_captures!(7: A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_from_captures!(8: A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::Parser;
+
+    #[test]
+    fn test_parse() {
+        let parser = Parser::from_str(
+            "Button A: X+94, Y+34\np=0,4 v=3,-3\nRegister A: 729\nx00 AND y00 -> z00",
+        );
+        let lines = parser.get_lines();
+        let line = |i: usize| Line {
+            source: "<input>",
+            number: i + 1,
+            text: &lines[i],
+        };
+
+        assert_eq!(
+            pattern!("Button A: X+{}, Y+{}").parse(&line(0)),
+            Ok((94i64, 34i64))
+        );
+        assert_eq!(
+            pattern!("p={},{} v={},{}").parse(&line(1)),
+            Ok((0i64, 4i64, 3i64, -3i64))
+        );
+        assert_eq!(
+            pattern!("Register {}: {}").parse(&line(2)),
+            Ok(('A', 729u64))
+        );
+        assert_eq!(
+            pattern!("{} {} {} -> {}").parse::<(String, String, String, String)>(&line(3)),
+            Ok((
+                "x00".to_string(),
+                "AND".to_string(),
+                "y00".to_string(),
+                "z00".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let line = Line {
+            source: "<input>",
+            number: 2,
+            text: "p=0,4 x=3,-3",
+        };
+
+        let error = pattern!("p={},{} v={},{}")
+            .parse::<(i64, i64, i64, i64)>(&line)
+            .unwrap_err();
+        assert_eq!((error.line, error.column), (2, 5));
+        assert_eq!(error.message, "expected \" v=\"");
+
+        let error = Pattern::new("q={}").captures(&line).unwrap_err();
+        assert_eq!(
+            (error.column, error.message.as_str()),
+            (1, "expected \"q=\"")
+        );
+
+        let error = Pattern::new("p=0,4").captures(&line).unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (6, " x=3,-3"));
+
+        let error = pattern!("p={},{} x={},{}")
+            .parse::<(i64, i64, i64, u8)>(&line)
+            .unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (11, "-3"));
+    }
+}
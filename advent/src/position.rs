@@ -1,6 +1,15 @@
-use std::ops::{Add, Sub};
+use std::{
+    cmp::Ordering,
+    iter::Sum,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
 
-use num_traits::{CheckedAdd, CheckedSub};
+use num_traits::{CheckedAdd, CheckedSub, PrimInt, Signed, Zero};
+
+mod lattice;
+mod point;
+pub use lattice::Lattice;
+pub use point::{Bounds, Point, Point3, Point4};
 
 #[allow(non_camel_case_types)]
 pub type Position_i64 = Position<i64>;
@@ -81,6 +90,114 @@ impl<T: CheckedSub> CheckedSub for Position<T> {
     }
 }
 
+impl<T: Mul<Output = T> + Copy> Mul<T> for Position<T> {
+    type Output = Position<T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Position {
+            x: self.x * rhs,
+            y: self.y * rhs,
+        }
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Position<T> {
+    type Output = Position<T>;
+
+    fn neg(self) -> Self::Output {
+        Position {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+impl<T: AddAssign> AddAssign for Position<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Position<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl<T: Zero> Sum for Position<T> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(
+            Position {
+                x: T::zero(),
+                y: T::zero(),
+            },
+            |a, b| a + b,
+        )
+    }
+}
+
+impl<'a, T: Zero + Copy + 'a> Sum<&'a Position<T>> for Position<T> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl<T: PartialOrd> Position<T> {
+    // Not `min`/`max`, which `Ord` already provides with a lexicographic meaning.
+    pub fn component_min(self, other: Self) -> Self {
+        Position {
+            x: if other.x < self.x { other.x } else { self.x },
+            y: if other.y < self.y { other.y } else { self.y },
+        }
+    }
+
+    pub fn component_max(self, other: Self) -> Self {
+        Position {
+            x: if other.x > self.x { other.x } else { self.x },
+            y: if other.y > self.y { other.y } else { self.y },
+        }
+    }
+}
+
+impl<T: Signed> Position<T> {
+    pub fn abs(&self) -> Self {
+        Position {
+            x: self.x.abs(),
+            y: self.y.abs(),
+        }
+    }
+
+    pub fn signum(&self) -> Self {
+        Position {
+            x: self.x.signum(),
+            y: self.y.signum(),
+        }
+    }
+}
+
+// Rotations use screen coordinates, where y grows downwards, so clockwise takes North to East.
+impl<T: Neg<Output = T>> Position<T> {
+    pub fn rotate_cw(self) -> Self {
+        Position {
+            x: -self.y,
+            y: self.x,
+        }
+    }
+
+    pub fn rotate_ccw(self) -> Self {
+        Position {
+            x: self.y,
+            y: -self.x,
+        }
+    }
+
+    pub fn rotate_180(self) -> Self {
+        -self
+    }
+}
+
 impl<T: CheckedSub + Default> Position<T> {
     pub fn checked_sub_x(&self, x: T) -> Option<Self> {
         self.checked_sub(&Self::x(x))
@@ -111,40 +228,784 @@ impl<T: Add<Output = T> + Default> Position<T> {
     }
 }
 
-impl<T: Add<Output = T> + std::cmp::PartialOrd + Copy> Position<T> {
-    pub fn limited_add_y(&self, y: T, limit: Self) -> Option<Self> {
-        let new_pos = Position {
-            x: self.x,
-            y: self.y + y,
+impl<T: Sub<Output = T> + Default> Position<T> {
+    pub fn sub_x(self, x: T) -> Self {
+        self - Position::<T>::x(x)
+    }
+
+    pub fn sub_y(self, y: T) -> Self {
+        self - Position::<T>::y(y)
+    }
+}
+
+// An axis-aligned rectangle of cells. `min` is inclusive and `max` is exclusive on both axes,
+// like a `Range`, so `Rect::from_size(w, h)` holds x in `0..w` and y in `0..h`. Use
+// `from_inclusive` when the input gives the last valid cell instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "T: std::fmt::Display",
+        deserialize = "T: std::str::FromStr"
+    ))
+)]
+pub struct Rect<T> {
+    pub min: Position<T>,
+    pub max: Position<T>,
+}
+
+impl<T: PrimInt> Rect<T> {
+    pub fn new(min: Position<T>, max: Position<T>) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_size(width: T, height: T) -> Self {
+        Self {
+            min: Position {
+                x: T::zero(),
+                y: T::zero(),
+            },
+            max: Position {
+                x: width,
+                y: height,
+            },
+        }
+    }
+
+    pub fn from_inclusive(min: Position<T>, last: Position<T>) -> Self {
+        Self {
+            min,
+            max: Position {
+                x: last.x + T::one(),
+                y: last.y + T::one(),
+            },
+        }
+    }
+
+    // The smallest rectangle holding every position, or None when there are none.
+    pub fn bounding<'a>(positions: impl IntoIterator<Item = &'a Position<T>>) -> Option<Self>
+    where
+        T: 'a,
+    {
+        let mut positions = positions.into_iter();
+        let first = *positions.next()?;
+        let (min, last) = positions.fold((first, first), |(min, max), p| {
+            (
+                Position {
+                    x: min.x.min(p.x),
+                    y: min.y.min(p.y),
+                },
+                Position {
+                    x: max.x.max(p.x),
+                    y: max.y.max(p.y),
+                },
+            )
+        });
+
+        Some(Self::from_inclusive(min, last))
+    }
+
+    pub fn width(&self) -> T {
+        self.max.x.saturating_sub(self.min.x)
+    }
+
+    pub fn height(&self) -> T {
+        self.max.y.saturating_sub(self.min.y)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width().is_zero() || self.height().is_zero()
+    }
+
+    pub fn contains(&self, pos: Position<T>) -> bool {
+        pos.x >= self.min.x && pos.x < self.max.x && pos.y >= self.min.y && pos.y < self.max.y
+    }
+
+    // The closest cell inside the rectangle. Panics on an empty rectangle.
+    pub fn clamp(&self, pos: Position<T>) -> Position<T> {
+        assert!(!self.is_empty(), "Cannot clamp to an empty Rect");
+
+        Position {
+            x: pos.x.clamp(self.min.x, self.max.x - T::one()),
+            y: pos.y.clamp(self.min.y, self.max.y - T::one()),
+        }
+    }
+
+    // Every cell in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = Position<T>> {
+        let Rect { min, max } = *self;
+        let mut next = (!self.is_empty()).then_some(min);
+
+        std::iter::from_fn(move || {
+            let current = next?;
+            next = if current.x + T::one() < max.x {
+                Some(Position {
+                    x: current.x + T::one(),
+                    y: current.y,
+                })
+            } else if current.y + T::one() < max.y {
+                Some(Position {
+                    x: min.x,
+                    y: current.y + T::one(),
+                })
+            } else {
+                None
+            };
+            Some(current)
+        })
+    }
+}
+
+impl<T: PrimInt + Signed> Position<T> {
+    pub fn neighbors4_in(self, bounds: Rect<T>) -> impl Iterator<Item = Self> {
+        Direction::ALL4
+            .into_iter()
+            .map(move |d| self + d)
+            .filter(move |p| bounds.contains(*p))
+    }
+
+    pub fn neighbors8_in(self, bounds: Rect<T>) -> impl Iterator<Item = Self> {
+        Direction::ALL8
+            .into_iter()
+            .map(move |d| self + d)
+            .filter(move |p| bounds.contains(*p))
+    }
+
+    // Every position reached by repeatedly adding `step`, starting with this one. Never ends,
+    // so bound it with `take`/`take_while` or use `ray_in`.
+    pub fn ray(self, step: impl Into<Self>) -> impl Iterator<Item = Self> {
+        let step = step.into();
+        std::iter::successors(Some(self), move |p| Some(*p + step))
+    }
+
+    // Like `ray`, but stops at the first position outside `bounds`.
+    pub fn ray_in(self, step: impl Into<Self>, bounds: Rect<T>) -> impl Iterator<Item = Self> {
+        self.ray(step).take_while(move |p| bounds.contains(*p))
+    }
+
+    // The smallest lattice step from here towards `other`, e.g. (2, -3) for a delta of (4, -6).
+    // Zero when both positions are the same.
+    pub fn lattice_step(self, other: Self) -> Self {
+        let delta = other - self;
+        let divisor = gcd(delta.x, delta.y);
+        if divisor.is_zero() {
+            return delta;
+        }
+
+        Position {
+            x: delta.x / divisor,
+            y: delta.y / divisor,
+        }
+    }
+
+    // Every lattice point on the segment from here to `other`, both ends included.
+    pub fn line_to(self, other: Self) -> impl Iterator<Item = Self> {
+        let delta = other - self;
+        let count = gcd(delta.x, delta.y).to_usize().unwrap() + 1;
+
+        self.ray(self.lattice_step(other)).take(count)
+    }
+
+    pub fn manhattan(self, other: Self) -> T {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    pub fn chebyshev(self, other: Self) -> T {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+
+    pub fn euclidean_sq(self, other: Self) -> T {
+        let dx = self.x - other.x;
+        let dy = self.y - other.y;
+        dx * dx + dy * dy
+    }
+
+    // Every position at a Manhattan distance of at most `r`, row by row from the top.
+    pub fn within_manhattan(self, r: T) -> impl Iterator<Item = Self> {
+        self.manhattan_rows(r, |dx, span| (dx < span).then(|| dx + T::one()))
+    }
+
+    // Every position at a Manhattan distance of exactly `r`, row by row from the top.
+    pub fn ring_manhattan(self, r: T) -> impl Iterator<Item = Self> {
+        self.manhattan_rows(r, |dx, span| (dx < span).then_some(span))
+    }
+
+    // Walks the rows of the diamond of radius `r`, using `next_dx` to step along a row whose
+    // cells run from `-span` to `span`.
+    fn manhattan_rows(
+        self,
+        r: T,
+        next_dx: impl Fn(T, T) -> Option<T>,
+    ) -> impl Iterator<Item = Self> {
+        let mut next = (r >= T::zero()).then(|| (-r, T::zero()));
+
+        std::iter::from_fn(move || {
+            let (dy, dx) = next?;
+            next = match next_dx(dx, r - dy.abs()) {
+                Some(dx) => Some((dy, dx)),
+                None if dy < r => {
+                    let dy = dy + T::one();
+                    Some((dy, dy.abs() - r))
+                }
+                None => None,
+            };
+
+            Some(Position {
+                x: self.x + dx,
+                y: self.y + dy,
+            })
+        })
+    }
+}
+
+// A wrap-around space of `width` by `height` cells, where leaving one edge re-enters on the
+// opposite one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RawTorus")
+)]
+pub struct Torus {
+    width: i64,
+    height: i64,
+}
+
+impl Torus {
+    pub const fn new(width: i64, height: i64) -> Self {
+        assert!(width > 0 && height > 0, "Torus dimensions must be positive");
+        Self { width, height }
+    }
+
+    pub const fn width(&self) -> i64 {
+        self.width
+    }
+
+    pub const fn height(&self) -> i64 {
+        self.height
+    }
+
+    pub fn normalize(&self, pos: Position_i64) -> Position_i64 {
+        Position {
+            x: pos.x.rem_euclid(self.width),
+            y: pos.y.rem_euclid(self.height),
+        }
+    }
+
+    // Where `pos` ends up after `steps` moves of `velocity`. Works for negative steps too.
+    pub fn advance(&self, pos: Position_i64, velocity: Position_i64, steps: i64) -> Position_i64 {
+        // Widened so the product can't overflow for large sizes.
+        let axis = |p: i64, v: i64, size: i64| {
+            let size = size as i128;
+            let moved = (v as i128).rem_euclid(size) * (steps as i128).rem_euclid(size);
+            ((p as i128).rem_euclid(size) + moved).rem_euclid(size) as i64
         };
 
-        if new_pos.y >= limit.y {
-            return None;
+        Position {
+            x: axis(pos.x, velocity.x, self.width),
+            y: axis(pos.y, velocity.y, self.height),
         }
+    }
 
-        Some(new_pos)
+    // Number of steps after which anything moving at `velocity` is back where it started.
+    pub fn period(&self, velocity: Position_i64) -> i64 {
+        let axis = |v: i64, size: i64| size / gcd(v.rem_euclid(size), size);
+        lcm(axis(velocity.x, self.width), axis(velocity.y, self.height))
     }
 
-    pub fn limited_add_x(&self, x: T, limit: Self) -> Option<Self> {
-        let new_pos = Position {
-            x: self.x + x,
-            y: self.y,
+    // Quadrants are numbered 0 (top left), 1 (top right), 2 (bottom left) and 3 (bottom
+    // right). Cells on the middle row or column of an odd-sized torus are in none of them.
+    pub fn quadrant(&self, pos: Position_i64) -> Option<usize> {
+        let pos = self.normalize(pos);
+        let half = |p: i64, size: i64| match (2 * p + 1).cmp(&size) {
+            Ordering::Less => Some(0),
+            Ordering::Equal => None,
+            Ordering::Greater => Some(1),
         };
 
-        if new_pos.x >= limit.x {
-            return None;
+        Some(half(pos.y, self.height)? * 2 + half(pos.x, self.width)?)
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawTorus {
+    width: i64,
+    height: i64,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawTorus> for Torus {
+    type Error = String;
+
+    fn try_from(raw: RawTorus) -> Result<Self, Self::Error> {
+        if raw.width <= 0 || raw.height <= 0 {
+            return Err(format!(
+                "Torus of {}x{} must have positive dimensions",
+                raw.width, raw.height
+            ));
         }
 
-        Some(new_pos)
+        Ok(Self::new(raw.width, raw.height))
     }
 }
 
-impl<T: Sub<Output = T> + Default> Position<T> {
-    pub fn sub_x(self, x: T) -> Self {
-        self - Position::<T>::x(x)
+pub fn gcd<T: PrimInt + Signed>(a: T, b: T) -> T {
+    if b.is_zero() {
+        a.abs()
+    } else {
+        gcd(b, a % b)
     }
+}
 
-    pub fn sub_y(self, y: T) -> Self {
-        self - Position::<T>::y(y)
+// Handy for combining periods: things repeating every `a` and every `b` steps line up again
+// after `lcm(a, b)` steps.
+pub fn lcm<T: PrimInt + Signed>(a: T, b: T) -> T {
+    if a.is_zero() || b.is_zero() {
+        return T::zero();
+    }
+
+    (a / gcd(a, b) * b).abs()
+}
+
+// Compass directions with y growing downwards, so North is one row up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    pub const ALL4: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    pub const ALL8: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    fn from_index(index: usize) -> Self {
+        Self::ALL8[index % 8]
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn turn_right(self) -> Self {
+        Self::from_index(self.index() + 2)
+    }
+
+    pub fn turn_left(self) -> Self {
+        Self::from_index(self.index() + 6)
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::from_index(self.index() + 4)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    // Accepts arrows (`^>v<`), compass letters (`NESW`) and `UDLR`.
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '^' | 'N' | 'U' => Some(Direction::North),
+            '>' | 'E' | 'R' => Some(Direction::East),
+            'v' | 'S' | 'D' => Some(Direction::South),
+            '<' | 'W' | 'L' => Some(Direction::West),
+            _ => None,
+        }
+    }
+
+    pub fn to_arrow(self) -> Option<char> {
+        match self {
+            Direction::North => Some('^'),
+            Direction::East => Some('>'),
+            Direction::South => Some('v'),
+            Direction::West => Some('<'),
+            _ => None,
+        }
+    }
+
+    pub fn offset<T: Signed>(self) -> Position<T> {
+        let (x, y) = match self {
+            Direction::North => (0, -1),
+            Direction::NorthEast => (1, -1),
+            Direction::East => (1, 0),
+            Direction::SouthEast => (1, 1),
+            Direction::South => (0, 1),
+            Direction::SouthWest => (-1, 1),
+            Direction::West => (-1, 0),
+            Direction::NorthWest => (-1, -1),
+        };
+
+        let unit = |v: i8| match v {
+            1 => T::one(),
+            -1 => -T::one(),
+            _ => T::zero(),
+        };
+        Position {
+            x: unit(x),
+            y: unit(y),
+        }
+    }
+}
+
+impl<T: Signed> From<Direction> for Position<T> {
+    fn from(direction: Direction) -> Self {
+        direction.offset()
+    }
+}
+
+impl<T: Signed> Add<Direction> for Position<T> {
+    type Output = Position<T>;
+
+    fn add(self, rhs: Direction) -> Self::Output {
+        self + rhs.offset()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_direction_turns() {
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Direction::North.turn_left(), Direction::West);
+        assert_eq!(Direction::NorthEast.turn_right(), Direction::SouthEast);
+        assert_eq!(Direction::SouthWest.reverse(), Direction::NorthEast);
+        assert!(Direction::ALL4
+            .iter()
+            .all(|d| d.turn_left().turn_right() == *d));
+        assert_eq!(
+            Direction::ALL8.iter().filter(|d| d.is_diagonal()).count(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_direction_offsets() {
+        let velocity: Position_i64 = Direction::North.into();
+        assert_eq!(velocity, Position { x: 0, y: -1 });
+        // Turning right matches the `Position { x: -v.y, y: v.x }` rotation used by day06.
+        let turned: Position_i64 = Direction::North.turn_right().into();
+        assert_eq!(
+            turned,
+            Position {
+                x: -velocity.y,
+                y: velocity.x
+            }
+        );
+
+        assert_eq!(
+            Position_isize { x: 3, y: 3 } + Direction::NorthWest,
+            Position_isize { x: 2, y: 2 }
+        );
+        assert_eq!(
+            Direction::ALL8
+                .iter()
+                .map(|d| d.offset::<i32>())
+                .fold(Position { x: 0, y: 0 }, |a, b| a + b),
+            Position { x: 0, y: 0 }
+        );
+    }
+
+    #[test]
+    fn test_direction_chars() {
+        let parsed = "^>v<"
+            .chars()
+            .filter_map(Direction::from_char)
+            .collect::<Vec<_>>();
+        assert_eq!(parsed, Direction::ALL4);
+        assert_eq!(
+            "NESW"
+                .chars()
+                .filter_map(Direction::from_char)
+                .collect::<Vec<_>>(),
+            parsed
+        );
+        assert_eq!(
+            parsed
+                .iter()
+                .filter_map(|d| d.to_arrow())
+                .collect::<String>(),
+            "^>v<"
+        );
+        assert_eq!(Direction::from_char('.'), None);
+    }
+
+    #[test]
+    fn test_rect() {
+        let rect = Rect::from_size(3isize, 2);
+
+        assert!(rect.contains(Position { x: 2, y: 1 }));
+        assert!(!rect.contains(Position { x: 3, y: 1 }));
+        assert!(!rect.contains(Position { x: 0, y: -1 }));
+        assert_eq!(
+            Rect::from_inclusive(Position { x: 0, y: 0 }, Position { x: 2, y: 1 }),
+            rect
+        );
+        assert_eq!(
+            rect.clamp(Position { x: 7, y: -4 }),
+            Position { x: 2, y: 0 }
+        );
+        assert_eq!(
+            rect.iter().collect::<Vec<_>>(),
+            [(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)].map(|(x, y)| Position { x, y })
+        );
+        assert_eq!(Rect::from_size(0usize, 5).iter().count(), 0);
+
+        let bounding = Rect::bounding(&[Position { x: 4, y: -1 }, Position { x: -2, y: 3 }]);
+        assert_eq!(
+            bounding,
+            Some(Rect::new(
+                Position { x: -2, y: -1 },
+                Position { x: 5, y: 4 }
+            ))
+        );
+        assert_eq!(bounding.unwrap().iter().count(), 35);
+    }
+
+    #[test]
+    fn test_neighbors_in() {
+        let rect = Rect::from_size(3i64, 3);
+
+        assert_eq!(Position { x: 0, y: 0 }.neighbors4_in(rect).count(), 2);
+        assert_eq!(Position { x: 1, y: 0 }.neighbors8_in(rect).count(), 5);
+        assert_eq!(Position { x: 1, y: 1 }.neighbors8_in(rect).count(), 8);
+        assert_eq!(
+            Position { x: 2, y: 1 }
+                .neighbors4_in(rect)
+                .collect::<Vec<_>>(),
+            vec![
+                Position { x: 2, y: 0 },
+                Position { x: 2, y: 2 },
+                Position { x: 1, y: 1 }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Position_i64 { x: 1, y: -2 };
+        let b = Position_i64 { x: -3, y: 1 };
+
+        assert_eq!(a.manhattan(b), 7);
+        assert_eq!(a.chebyshev(b), 4);
+        assert_eq!(a.euclidean_sq(b), 25);
+        assert_eq!(a.manhattan(a), 0);
+    }
+
+    #[test]
+    fn test_manhattan_iterators() {
+        let center = Position_isize { x: 5, y: 5 };
+
+        assert_eq!(
+            center.within_manhattan(1).collect::<Vec<_>>(),
+            [(5, 4), (4, 5), (5, 5), (6, 5), (5, 6)].map(|(x, y)| Position { x, y })
+        );
+        assert_eq!(
+            center.ring_manhattan(2).collect::<Vec<_>>(),
+            [
+                (5, 3),
+                (4, 4),
+                (6, 4),
+                (3, 5),
+                (7, 5),
+                (4, 6),
+                (6, 6),
+                (5, 7)
+            ]
+            .map(|(x, y)| Position { x, y })
+        );
+        assert_eq!(center.ring_manhattan(0).collect::<Vec<_>>(), vec![center]);
+        assert_eq!(center.within_manhattan(-1).count(), 0);
+
+        for r in [2, 20] {
+            let within = center.within_manhattan(r).collect::<Vec<_>>();
+            assert_eq!(within.len() as isize, 2 * r * (r + 1) + 1);
+            assert!(within.iter().all(|p| p.manhattan(center) <= r));
+            assert_eq!(
+                center.ring_manhattan(r).count(),
+                within.iter().filter(|p| p.manhattan(center) == r).count()
+            );
+        }
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let position = Position_i64 { x: 0, y: 4 };
+        let velocity = Position_i64 { x: 3, y: -3 };
+
+        assert_eq!(position + velocity * 5, Position { x: 15, y: -11 });
+        // Day08 antinode: 2a - b
+        let (a, b) = (Position_i64 { x: 4, y: 3 }, Position_i64 { x: 5, y: 5 });
+        assert_eq!(a * 2 - b, Position { x: 3, y: 1 });
+        assert_eq!(-velocity, Position { x: -3, y: 3 });
+
+        let mut p = position;
+        p += velocity;
+        p -= Position { x: 1, y: 1 };
+        assert_eq!(p, Position { x: 2, y: 0 });
+
+        assert_eq!(
+            [a, b, velocity].iter().sum::<Position_i64>(),
+            Position { x: 12, y: 5 }
+        );
+        assert_eq!(
+            Vec::<Position_usize>::new()
+                .into_iter()
+                .sum::<Position_usize>(),
+            Position { x: 0, y: 0 }
+        );
+    }
+
+    #[test]
+    fn test_component_ops() {
+        let a = Position_i64 { x: -4, y: 7 };
+        let b = Position_i64 { x: 2, y: -9 };
+
+        assert_eq!(a.component_min(b), Position { x: -4, y: -9 });
+        assert_eq!(a.component_max(b), Position { x: 2, y: 7 });
+        assert_eq!(a.abs(), Position { x: 4, y: 7 });
+        assert_eq!(b.signum(), Position { x: 1, y: -1 });
+        assert_eq!(
+            Position_usize { x: 3, y: 1 }.component_min(Position { x: 1, y: 2 }),
+            Position { x: 1, y: 1 }
+        );
+    }
+
+    #[test]
+    fn test_rotations() {
+        let north: Position_i64 = Direction::North.into();
+
+        for d in Direction::ALL8 {
+            let offset: Position_i64 = d.into();
+            assert_eq!(offset.rotate_cw(), d.turn_right().into());
+            assert_eq!(offset.rotate_ccw(), d.turn_left().into());
+            assert_eq!(offset.rotate_180(), d.reverse().into());
+        }
+        assert_eq!(north.rotate_cw().rotate_cw().rotate_cw().rotate_cw(), north);
+    }
+
+    #[test]
+    fn test_torus() {
+        let torus = Torus::new(11, 7);
+        let robot = Position_i64 { x: 2, y: 4 };
+        let velocity = Position_i64 { x: 2, y: -3 };
+
+        assert_eq!(
+            torus.normalize(Position { x: -1, y: 15 }),
+            Position { x: 10, y: 1 }
+        );
+        assert_eq!(torus.advance(robot, velocity, 5), Position { x: 1, y: 3 });
+        assert_eq!(
+            torus.advance(torus.advance(robot, velocity, 123), velocity, -123),
+            robot
+        );
+
+        let period = torus.period(velocity);
+        assert_eq!(period, 77);
+        assert_eq!(torus.advance(robot, velocity, period), robot);
+        assert_eq!(torus.period(Position { x: 0, y: 7 }), 1);
+        assert_eq!(Torus::new(6, 4).period(Position { x: 2, y: 2 }), 6);
+
+        assert_eq!(torus.quadrant(Position { x: 0, y: 0 }), Some(0));
+        assert_eq!(torus.quadrant(Position { x: 6, y: 2 }), Some(1));
+        assert_eq!(torus.quadrant(Position { x: 4, y: 6 }), Some(2));
+        assert_eq!(torus.quadrant(Position { x: 5, y: 1 }), None);
+        assert_eq!(torus.quadrant(Position { x: 1, y: 3 }), None);
+        assert_eq!(Torus::new(4, 4).quadrant(Position { x: 2, y: 2 }), Some(3));
+
+        // (-1) * (-1) steps on a torus too wide to multiply sizes in an i64.
+        let wide = Torus::new(4_000_000_000, 1);
+        let velocity = Position_i64 {
+            x: 3_999_999_999,
+            y: 0,
+        };
+        assert_eq!(
+            wide.advance(Position { x: 0, y: 0 }, velocity, 3_999_999_999),
+            Position { x: 1, y: 0 }
+        );
+    }
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12, -18), 6);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(-4, 6), 12);
+        assert_eq!(lcm(101i64, 103), 10403);
+        assert_eq!(lcm(0, 7), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Torus dimensions must be positive")]
+    fn test_empty_torus() {
+        Torus::new(0, 7);
+    }
+
+    #[test]
+    fn test_rays() {
+        let start = Position_isize { x: 1, y: 1 };
+
+        assert_eq!(
+            start.ray(Direction::SouthEast).take(4).collect::<Vec<_>>(),
+            [(1, 1), (2, 2), (3, 3), (4, 4)].map(|(x, y)| Position { x, y })
+        );
+        assert_eq!(
+            start
+                .ray_in(Position_isize { x: 2, y: 1 }, Rect::from_size(6, 6))
+                .collect::<Vec<_>>(),
+            [(1, 1), (3, 2), (5, 3)].map(|(x, y)| Position { x, y })
+        );
+        assert_eq!(
+            start.ray_in(Direction::West, Rect::from_size(6, 6)).count(),
+            2
+        );
+        assert_eq!(
+            Position { x: 9, y: 9 }
+                .ray_in(Direction::West, Rect::from_size(6, 6))
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_line_to() {
+        let a = Position_i64 { x: 0, y: 6 };
+        let b = Position_i64 { x: 4, y: 0 };
+
+        assert_eq!(a.lattice_step(b), Position { x: 2, y: -3 });
+        assert_eq!(
+            a.line_to(b).collect::<Vec<_>>(),
+            [(0, 6), (2, 3), (4, 0)].map(|(x, y)| Position { x, y })
+        );
+        assert_eq!(b.line_to(a).last(), Some(a));
+        assert_eq!(a.line_to(Position { x: 0, y: 2 }).count(), 5);
+        assert_eq!(a.line_to(Position { x: 3, y: 4 }).count(), 2);
+        assert_eq!(a.line_to(a).collect::<Vec<_>>(), vec![a]);
+        assert_eq!(a.lattice_step(a), Position { x: 0, y: 0 });
     }
 }
@@ -0,0 +1,112 @@
+use std::hash::Hash;
+
+use num_traits::{PrimInt, Signed};
+
+use super::{Direction, Point, Position};
+
+// The operations shared by `Position` and `Point`, so that searches and region code can be
+// written once and used in any number of dimensions.
+pub trait Lattice: Copy + Eq + Hash {
+    type Coord: PrimInt + Signed;
+
+    fn manhattan(self, other: Self) -> Self::Coord;
+
+    // Neighbours that differ by one on a single axis.
+    fn neighbors_orthogonal(self) -> impl Iterator<Item = Self>;
+
+    // Neighbours that differ by at most one on every axis, diagonals included.
+    fn neighbors_all(self) -> impl Iterator<Item = Self>;
+
+    fn within_manhattan(self, r: Self::Coord) -> impl Iterator<Item = Self>;
+}
+
+impl<T: PrimInt + Signed + Hash> Lattice for Position<T> {
+    type Coord = T;
+
+    fn manhattan(self, other: Self) -> T {
+        Position::manhattan(self, other)
+    }
+
+    fn neighbors_orthogonal(self) -> impl Iterator<Item = Self> {
+        Direction::ALL4.into_iter().map(move |d| self + d)
+    }
+
+    fn neighbors_all(self) -> impl Iterator<Item = Self> {
+        Direction::ALL8.into_iter().map(move |d| self + d)
+    }
+
+    fn within_manhattan(self, r: T) -> impl Iterator<Item = Self> {
+        Position::within_manhattan(self, r)
+    }
+}
+
+impl<T: PrimInt + Signed + Hash, const N: usize> Lattice for Point<T, N> {
+    type Coord = T;
+
+    fn manhattan(self, other: Self) -> T {
+        Point::manhattan(self, other)
+    }
+
+    fn neighbors_orthogonal(self) -> impl Iterator<Item = Self> {
+        Point::neighbors_orthogonal(self)
+    }
+
+    fn neighbors_all(self) -> impl Iterator<Item = Self> {
+        Point::neighbors_all(self)
+    }
+
+    fn within_manhattan(self, r: T) -> impl Iterator<Item = Self> {
+        Point::within_manhattan(self, r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::position::{Point3, Position_i64};
+
+    // Everything reachable from `start` in at most `steps` orthogonal moves.
+    fn reachable<P: Lattice>(start: P, steps: usize) -> HashSet<P> {
+        let mut seen = HashSet::from([start]);
+        let mut frontier = vec![start];
+        for _ in 0..steps {
+            frontier = frontier
+                .into_iter()
+                .flat_map(|p| p.neighbors_orthogonal())
+                .filter(|p| seen.insert(*p))
+                .collect();
+        }
+
+        seen
+    }
+
+    #[test]
+    fn test_generic_search() {
+        let position = Position_i64 { x: 3, y: -1 };
+        assert_eq!(
+            reachable(position, 2),
+            Lattice::within_manhattan(position, 2).collect()
+        );
+        assert_eq!(reachable(position, 2).len(), 13);
+
+        let point = Point3::new([0i64, 1, 2]);
+        assert_eq!(
+            reachable(point, 2),
+            Lattice::within_manhattan(point, 2).collect()
+        );
+        assert_eq!(reachable(point, 2).len(), 25);
+    }
+
+    #[test]
+    fn test_neighbor_counts() {
+        let position = Position_i64 { x: 0, y: 0 };
+        assert_eq!(Lattice::neighbors_all(position).count(), 8);
+        assert_eq!(Lattice::neighbors_orthogonal(position).count(), 4);
+
+        let point = Point::<i64, 4>::origin();
+        assert_eq!(Lattice::neighbors_all(point).count(), 80);
+        assert_eq!(Lattice::neighbors_orthogonal(point).count(), 8);
+    }
+}
@@ -0,0 +1,513 @@
+use std::{
+    iter::Sum,
+    ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign},
+};
+
+use num_traits::{CheckedAdd, CheckedSub, PrimInt, Signed, Zero};
+
+use super::{gcd, Position, Rect};
+
+pub type Point3<T> = Point<T, 3>;
+pub type Point4<T> = Point<T, 4>;
+
+// A position with `N` coordinates. `Point<T, 2>` converts to and from `Position<T>`, with the
+// coordinates in x, y order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point<T, const N: usize> {
+    pub coords: [T; N],
+}
+
+impl<T, const N: usize> Point<T, N> {
+    pub const fn new(coords: [T; N]) -> Self {
+        Self { coords }
+    }
+}
+
+impl<T: Zero + Copy, const N: usize> Point<T, N> {
+    pub fn origin() -> Self {
+        Self {
+            coords: [T::zero(); N],
+        }
+    }
+}
+
+// An axis-aligned box of points. Like `Rect`, `min` is inclusive and `max` is exclusive on
+// every axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bounds<T, const N: usize> {
+    pub min: Point<T, N>,
+    pub max: Point<T, N>,
+}
+
+impl<T: PrimInt, const N: usize> Bounds<T, N> {
+    pub fn new(min: Point<T, N>, max: Point<T, N>) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_size(size: [T; N]) -> Self {
+        Self {
+            min: Point::new([T::zero(); N]),
+            max: Point::new(size),
+        }
+    }
+
+    pub fn from_inclusive(min: Point<T, N>, last: Point<T, N>) -> Self {
+        Self {
+            min,
+            max: Point::new(last.coords.map(|c| c + T::one())),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..N).any(|axis| self.max[axis] <= self.min[axis])
+    }
+
+    pub fn contains(&self, p: Point<T, N>) -> bool {
+        (0..N).all(|axis| p[axis] >= self.min[axis] && p[axis] < self.max[axis])
+    }
+}
+
+impl<T> From<Rect<T>> for Bounds<T, 2> {
+    fn from(rect: Rect<T>) -> Self {
+        Self {
+            min: rect.min.into(),
+            max: rect.max.into(),
+        }
+    }
+}
+
+impl<T, const N: usize> Index<usize> for Point<T, N> {
+    type Output = T;
+
+    fn index(&self, axis: usize) -> &Self::Output {
+        &self.coords[axis]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for Point<T, N> {
+    fn index_mut(&mut self, axis: usize) -> &mut Self::Output {
+        &mut self.coords[axis]
+    }
+}
+
+impl<T> From<Position<T>> for Point<T, 2> {
+    fn from(p: Position<T>) -> Self {
+        Self { coords: [p.x, p.y] }
+    }
+}
+
+impl<T> From<Point<T, 2>> for Position<T> {
+    fn from(p: Point<T, 2>) -> Self {
+        let [x, y] = p.coords;
+        Position { x, y }
+    }
+}
+
+impl<T: Add<Output = T> + Copy, const N: usize> Add for Point<T, N> {
+    type Output = Point<T, N>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Point {
+            coords: std::array::from_fn(|i| self.coords[i] + rhs.coords[i]),
+        }
+    }
+}
+
+impl<T: CheckedAdd + Copy, const N: usize> CheckedAdd for Point<T, N> {
+    fn checked_add(&self, rhs: &Self) -> Option<Self::Output> {
+        let mut coords = self.coords;
+        for (c, r) in coords.iter_mut().zip(rhs.coords.iter()) {
+            *c = c.checked_add(r)?;
+        }
+        Some(Point { coords })
+    }
+}
+
+impl<T: Sub<Output = T> + Copy, const N: usize> Sub for Point<T, N> {
+    type Output = Point<T, N>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Point {
+            coords: std::array::from_fn(|i| self.coords[i] - rhs.coords[i]),
+        }
+    }
+}
+
+impl<T: CheckedSub + Copy, const N: usize> CheckedSub for Point<T, N> {
+    fn checked_sub(&self, rhs: &Self) -> Option<Self::Output> {
+        let mut coords = self.coords;
+        for (c, r) in coords.iter_mut().zip(rhs.coords.iter()) {
+            *c = c.checked_sub(r)?;
+        }
+        Some(Point { coords })
+    }
+}
+
+impl<T: Mul<Output = T> + Copy, const N: usize> Mul<T> for Point<T, N> {
+    type Output = Point<T, N>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Point {
+            coords: self.coords.map(|c| c * rhs),
+        }
+    }
+}
+
+impl<T: Neg<Output = T>, const N: usize> Neg for Point<T, N> {
+    type Output = Point<T, N>;
+
+    fn neg(self) -> Self::Output {
+        Point {
+            coords: self.coords.map(|c| -c),
+        }
+    }
+}
+
+impl<T: AddAssign + Copy, const N: usize> AddAssign for Point<T, N> {
+    fn add_assign(&mut self, rhs: Self) {
+        for (c, r) in self.coords.iter_mut().zip(rhs.coords) {
+            *c += r;
+        }
+    }
+}
+
+impl<T: SubAssign + Copy, const N: usize> SubAssign for Point<T, N> {
+    fn sub_assign(&mut self, rhs: Self) {
+        for (c, r) in self.coords.iter_mut().zip(rhs.coords) {
+            *c -= r;
+        }
+    }
+}
+
+impl<T: Zero + Copy, const N: usize> Sum for Point<T, N> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::origin(), |a, b| a + b)
+    }
+}
+
+impl<'a, T: Zero + Copy + 'a, const N: usize> Sum<&'a Point<T, N>> for Point<T, N> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl<T: PartialOrd + Copy, const N: usize> Point<T, N> {
+    pub fn component_min(self, other: Self) -> Self {
+        Point {
+            coords: std::array::from_fn(|i| {
+                if other.coords[i] < self.coords[i] {
+                    other.coords[i]
+                } else {
+                    self.coords[i]
+                }
+            }),
+        }
+    }
+
+    pub fn component_max(self, other: Self) -> Self {
+        Point {
+            coords: std::array::from_fn(|i| {
+                if other.coords[i] > self.coords[i] {
+                    other.coords[i]
+                } else {
+                    self.coords[i]
+                }
+            }),
+        }
+    }
+}
+
+impl<T: PrimInt + Signed, const N: usize> Point<T, N> {
+    pub fn abs(&self) -> Self {
+        Point {
+            coords: self.coords.map(|c| c.abs()),
+        }
+    }
+
+    pub fn signum(&self) -> Self {
+        Point {
+            coords: self.coords.map(|c| c.signum()),
+        }
+    }
+
+    pub fn manhattan(self, other: Self) -> T {
+        (self - other)
+            .coords
+            .iter()
+            .fold(T::zero(), |sum, c| sum + c.abs())
+    }
+
+    pub fn chebyshev(self, other: Self) -> T {
+        (self - other)
+            .coords
+            .iter()
+            .fold(T::zero(), |max, c| max.max(c.abs()))
+    }
+
+    pub fn euclidean_sq(self, other: Self) -> T {
+        (self - other)
+            .coords
+            .iter()
+            .fold(T::zero(), |sum, c| sum + *c * *c)
+    }
+
+    // The 2N neighbours one step along a single axis, axis by axis, negative step first.
+    pub fn neighbors_orthogonal(self) -> impl Iterator<Item = Self> {
+        (0..N).flat_map(move |axis| {
+            [-T::one(), T::one()].into_iter().map(move |step| {
+                let mut p = self;
+                p.coords[axis] = p.coords[axis] + step;
+                p
+            })
+        })
+    }
+
+    // The 3^N - 1 neighbours that differ by at most one on every axis, diagonals included.
+    pub fn neighbors_all(self) -> impl Iterator<Item = Self> {
+        self.cube(T::one()).filter(move |p| *p != self)
+    }
+
+    pub fn neighbors_orthogonal_in(self, bounds: Bounds<T, N>) -> impl Iterator<Item = Self> {
+        self.neighbors_orthogonal()
+            .filter(move |p| bounds.contains(*p))
+    }
+
+    pub fn neighbors_all_in(self, bounds: Bounds<T, N>) -> impl Iterator<Item = Self> {
+        self.neighbors_all().filter(move |p| bounds.contains(*p))
+    }
+
+    // Every point at a Manhattan distance of at most `r`. This walks the whole cube of side
+    // 2r + 1, so it gets slow for large `r` in many dimensions.
+    pub fn within_manhattan(self, r: T) -> impl Iterator<Item = Self> {
+        self.cube(r).filter(move |p| p.manhattan(self) <= r)
+    }
+
+    // Every point at a Manhattan distance of exactly `r`.
+    pub fn ring_manhattan(self, r: T) -> impl Iterator<Item = Self> {
+        self.cube(r).filter(move |p| p.manhattan(self) == r)
+    }
+
+    // Every point reached by repeatedly adding `step`, starting with this one. Never ends,
+    // so bound it with `take`/`take_while` or use `ray_in`.
+    pub fn ray(self, step: Self) -> impl Iterator<Item = Self> {
+        std::iter::successors(Some(self), move |p| Some(*p + step))
+    }
+
+    // Like `ray`, but stops at the first point outside `bounds`.
+    pub fn ray_in(self, step: Self, bounds: Bounds<T, N>) -> impl Iterator<Item = Self> {
+        self.ray(step).take_while(move |p| bounds.contains(*p))
+    }
+
+    // The smallest lattice step from here towards `other`. Zero when both points are the same.
+    pub fn lattice_step(self, other: Self) -> Self {
+        let delta = other - self;
+        let divisor = delta.coords.into_iter().fold(T::zero(), gcd);
+        if divisor.is_zero() {
+            return delta;
+        }
+
+        Point {
+            coords: delta.coords.map(|c| c / divisor),
+        }
+    }
+
+    // Every lattice point on the segment from here to `other`, both ends included.
+    pub fn line_to(self, other: Self) -> impl Iterator<Item = Self> {
+        let delta = other - self;
+        let count = delta
+            .coords
+            .into_iter()
+            .fold(T::zero(), gcd)
+            .to_usize()
+            .unwrap()
+            + 1;
+
+        self.ray(self.lattice_step(other)).take(count)
+    }
+
+    // Every point that differs by at most `r` on each axis, counting like an odometer with the
+    // last axis turning fastest.
+    fn cube(self, r: T) -> impl Iterator<Item = Self> {
+        let mut next = (r >= T::zero()).then(|| self.coords.map(|c| c - r));
+
+        std::iter::from_fn(move || {
+            let current = next?;
+            next = None;
+            let mut coords = current;
+            for axis in (0..N).rev() {
+                if coords[axis] < self.coords[axis] + r {
+                    coords[axis] = coords[axis] + T::one();
+                    next = Some(coords);
+                    break;
+                }
+                coords[axis] = self.coords[axis] - r;
+            }
+
+            Some(Point { coords: current })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::position::Position_i64;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Point3::new([1i64, -2, 3]);
+        let b = Point3::new([4i64, 0, -1]);
+
+        assert_eq!(a + b, Point::new([5, -2, 2]));
+        assert_eq!(a - b, Point::new([-3, -2, 4]));
+        assert_eq!(a * 2 - b, Point::new([-2, -4, 7]));
+        assert_eq!(-a, Point::new([-1, 2, -3]));
+        assert_eq!([a, b].iter().sum::<Point3<i64>>(), a + b);
+        assert_eq!(a.component_min(b), Point::new([1, -2, -1]));
+        assert_eq!(a.component_max(b), Point::new([4, 0, 3]));
+        assert_eq!(a.signum(), Point::new([1, -1, 1]));
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+
+        let u = Point3::new([0u8, 1, 255]);
+        assert_eq!(u.checked_add(&Point::new([1, 1, 1])), None);
+        assert_eq!(
+            u.checked_sub(&Point::new([0, 1, 5])),
+            Some(Point::new([0, 0, 250]))
+        );
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Point4::new([0i32, 0, 0, 0]);
+        let b = Point4::new([3i32, -4, 1, -1]);
+
+        assert_eq!(a.manhattan(b), 9);
+        assert_eq!(a.chebyshev(b), 4);
+        assert_eq!(a.euclidean_sq(b), 27);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let p = Point3::new([0i64, 0, 0]);
+
+        assert_eq!(p.neighbors_orthogonal().count(), 6);
+        assert!(p.neighbors_orthogonal().all(|n| n.manhattan(p) == 1));
+        assert_eq!(p.neighbors_all().count(), 26);
+        assert!(p.neighbors_all().all(|n| n.chebyshev(p) == 1));
+        assert_eq!(Point4::new([0i64; 4]).neighbors_all().count(), 80);
+    }
+
+    #[test]
+    fn test_bounds() {
+        let bounds = Bounds::from_size([2i64, 3, 4]);
+        let corner = Point3::new([0i64, 0, 0]);
+
+        assert!(bounds.contains(Point::new([1, 2, 3])));
+        assert!(!bounds.contains(Point::new([1, 3, 3])));
+        assert!(!bounds.contains(Point::new([-1, 0, 0])));
+        assert!(Bounds::from_size([2i64, 0, 4]).is_empty());
+        assert_eq!(
+            Bounds::from_inclusive(corner, Point::new([1, 2, 3])),
+            bounds
+        );
+
+        assert_eq!(corner.neighbors_orthogonal_in(bounds).count(), 3);
+        assert_eq!(corner.neighbors_all_in(bounds).count(), 7);
+        assert_eq!(
+            Point::new([1i64, 1, 1]).neighbors_all_in(bounds).count(),
+            3 * 2 * 3 - 1
+        );
+    }
+
+    #[test]
+    fn test_manhattan_radius() {
+        let center = Point3::new([5i64, -5, 0]);
+
+        // Octahedral numbers: 1, 7, 25, 63, ...
+        let counts = (0..4)
+            .map(|r| center.within_manhattan(r).count())
+            .collect::<Vec<_>>();
+        assert_eq!(counts, vec![1, 7, 25, 63]);
+        assert!(center.within_manhattan(3).all(|p| p.manhattan(center) <= 3));
+        assert_eq!(center.ring_manhattan(2).count(), 18);
+        assert!(center.ring_manhattan(2).all(|p| p.manhattan(center) == 2));
+        assert_eq!(center.within_manhattan(-1).count(), 0);
+
+        let position = Position_i64 { x: 1, y: 2 };
+        let point: Point<i64, 2> = position.into();
+        assert_eq!(
+            point
+                .within_manhattan(3)
+                .map(Into::<Position_i64>::into)
+                .collect::<std::collections::HashSet<_>>(),
+            position.within_manhattan(3).collect()
+        );
+    }
+
+    #[test]
+    fn test_rays_and_lines() {
+        let start = Point3::new([1i64, 2, 3]);
+        let step = Point3::new([2, -1, 0]);
+
+        assert_eq!(start.ray(step).nth(3), Some(Point3::new([7, -1, 3])));
+        let bounds = Bounds::from_size([6i64, 6, 6]);
+        assert_eq!(start.ray_in(step, bounds).count(), 3);
+
+        let end = Point3::new([7i64, -1, 6]);
+        assert_eq!(start.lattice_step(end), Point3::new([2, -1, 1]));
+        assert_eq!(
+            start.line_to(end).collect::<Vec<_>>(),
+            vec![start, Point3::new([3, 1, 4]), Point3::new([5, 0, 5]), end]
+        );
+        assert_eq!(start.lattice_step(start), Point3::origin());
+        assert_eq!(start.line_to(start).collect::<Vec<_>>(), vec![start]);
+
+        let position = Position_i64 { x: 0, y: 0 };
+        let target = Position_i64 { x: 4, y: -6 };
+        assert_eq!(
+            Point::from(position)
+                .line_to(target.into())
+                .map(Into::<Position_i64>::into)
+                .collect::<Vec<_>>(),
+            position.line_to(target).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_position_conversion() {
+        let position = Position_i64 { x: 3, y: -7 };
+        let point: Point<i64, 2> = position.into();
+
+        assert_eq!(point, Point::new([3, -7]));
+        // `Position::from` is the inherent numeric conversion, so go through `Into` instead.
+        let back: Position_i64 = point.into();
+        assert_eq!(back, position);
+        assert_eq!(
+            point.manhattan(Point::origin()),
+            position.manhattan(Position { x: 0, y: 0 })
+        );
+        assert_eq!(
+            point
+                .neighbors_all()
+                .map(Into::<Position_i64>::into)
+                .collect::<std::collections::HashSet<_>>(),
+            position
+                .neighbors8_in(crate::position::Rect::new(
+                    Position { x: -10, y: -10 },
+                    Position { x: 10, y: 10 }
+                ))
+                .collect()
+        );
+
+        let rect = Rect::from_size(3i64, 2);
+        let bounds: Bounds<i64, 2> = rect.into();
+        assert_eq!(
+            rect.iter().filter(|p| bounds.contains((*p).into())).count(),
+            6
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::HashSet;
+
+use crate::{
+    grid::Grid,
+    position::{Direction, Position_isize, Rect},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    pub id: usize,
+    // In reading order, so `cells[0]` is the top-left cell of the region.
+    pub cells: Vec<Position_isize>,
+    pub perimeter: usize,
+    // Straight edge runs. A region's side count equals its corner count.
+    pub sides: usize,
+    pub bounds: Rect<isize>,
+    // Holes are the connected pockets of other cells that can't reach the edge of the grid
+    // without crossing this region. `encloses` lists the ids of every region inside them.
+    pub holes: usize,
+    pub encloses: Vec<usize>,
+}
+
+impl Region {
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Regions {
+    // The id of the region each cell belongs to.
+    pub labels: Grid<usize>,
+    // Indexed by id, ordered by each region's first cell in reading order.
+    pub regions: Vec<Region>,
+}
+
+impl Regions {
+    pub fn region_at(&self, pos: Position_isize) -> Option<&Region> {
+        self.labels.get(pos).map(|id| &self.regions[*id])
+    }
+}
+
+// Splits the grid into 4-connected regions, where two neighbouring cells share a region when
+// `same_region` says so.
+pub fn label<T>(grid: &Grid<T>, mut same_region: impl FnMut(&T, &T) -> bool) -> Regions {
+    let mut labels = grid.map(|_| usize::MAX);
+    let mut members = Vec::new();
+
+    for start in grid.positions() {
+        if labels[start] != usize::MAX {
+            continue;
+        }
+
+        let id = members.len();
+        labels[start] = id;
+        let mut cells = vec![start];
+        let mut next = 0;
+        while next < cells.len() {
+            let pos = cells[next];
+            next += 1;
+            for neighbor in pos.neighbors4_in(grid.bounds()) {
+                if labels[neighbor] == usize::MAX && same_region(&grid[pos], &grid[neighbor]) {
+                    labels[neighbor] = id;
+                    cells.push(neighbor);
+                }
+            }
+        }
+
+        cells.sort_by_key(|p| (p.y, p.x));
+        members.push(cells);
+    }
+
+    let regions = members
+        .into_iter()
+        .enumerate()
+        .map(|(id, cells)| measure(&labels, id, cells))
+        .collect();
+
+    Regions { labels, regions }
+}
+
+fn measure(labels: &Grid<usize>, id: usize, cells: Vec<Position_isize>) -> Region {
+    let inside = |pos: Position_isize| labels.get(pos) == Some(&id);
+
+    let mut perimeter = 0;
+    let mut sides = 0;
+    for &pos in &cells {
+        perimeter += Direction::ALL4
+            .iter()
+            .filter(|d| !inside(pos + **d))
+            .count();
+
+        // Each corner of the cell is either convex (both edges open), concave (both edges
+        // inside but the diagonal outside) or not a corner of the region at all.
+        for d in Direction::ALL4 {
+            let (a, b) = (inside(pos + d), inside(pos + d.turn_right()));
+            let diagonal = inside(pos + d + d.turn_right());
+            if (!a && !b) || (a && b && !diagonal) {
+                sides += 1;
+            }
+        }
+    }
+
+    let bounds = Rect::bounding(&cells).unwrap();
+    let (holes, encloses) = find_holes(labels, id, bounds);
+
+    Region {
+        id,
+        cells,
+        perimeter,
+        sides,
+        bounds,
+        holes,
+        encloses,
+    }
+}
+
+// Floods the region's bounding box, grown by one cell on each side, from its outer ring. Any
+// cell of another region left unreached is in a hole.
+fn find_holes(labels: &Grid<usize>, id: usize, bounds: Rect<isize>) -> (usize, Vec<usize>) {
+    let frame = Rect::new(
+        bounds.min - Position_isize { x: 1, y: 1 },
+        bounds.max + Position_isize { x: 1, y: 1 },
+    );
+    let passable = |pos: Position_isize| labels.get(pos) != Some(&id);
+
+    let mut reached = HashSet::new();
+    let mut to_visit = frame
+        .iter()
+        .filter(|p| !bounds.contains(*p))
+        .collect::<Vec<_>>();
+    reached.extend(to_visit.iter().copied());
+    while let Some(pos) = to_visit.pop() {
+        for next in pos.neighbors4_in(frame) {
+            if passable(next) && reached.insert(next) {
+                to_visit.push(next);
+            }
+        }
+    }
+
+    let mut holes = 0;
+    let mut encloses = HashSet::new();
+    for start in bounds.iter() {
+        if !passable(start) || !reached.insert(start) {
+            continue;
+        }
+
+        holes += 1;
+        let mut to_visit = vec![start];
+        while let Some(pos) = to_visit.pop() {
+            encloses.insert(labels[pos]);
+            for next in pos.neighbors4_in(bounds) {
+                if passable(next) && reached.insert(next) {
+                    to_visit.push(next);
+                }
+            }
+        }
+    }
+
+    let mut encloses = encloses.into_iter().collect::<Vec<_>>();
+    encloses.sort();
+    (holes, encloses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::Parser;
+
+    #[test]
+    fn test_garden_example() {
+        let grid = Parser::from_str(
+            "RRRRIICCFF\nRRRRIICCCF\nVVRRRCCFFF\nVVRCCCJFFF\nVVVVCJJCFE\n\
+             VVIVCCJJEE\nVVIIICJJEE\nMIIIIIJJEE\nMIIISIJEEE\nMMMISSJEEE",
+        )
+        .as_grid();
+        let regions = label(&grid, |a, b| a == b);
+
+        assert_eq!(regions.regions.len(), 11);
+        assert_eq!(
+            regions
+                .regions
+                .iter()
+                .map(|r| r.area() * r.perimeter)
+                .sum::<usize>(),
+            1930
+        );
+        assert_eq!(
+            regions
+                .regions
+                .iter()
+                .map(|r| r.area() * r.sides)
+                .sum::<usize>(),
+            1206
+        );
+
+        let r = &regions.regions[0];
+        assert_eq!(grid[r.cells[0]], 'R');
+        assert_eq!((r.area(), r.perimeter, r.sides), (12, 18, 10));
+        assert_eq!(
+            r.bounds,
+            Rect::new(Position_isize { x: 0, y: 0 }, Position_isize { x: 5, y: 4 })
+        );
+    }
+
+    #[test]
+    fn test_holes() {
+        let grid = Parser::from_str("AAAAAA\nABBBAA\nABAAAA\nAAAAXA\nAAAAAA").as_grid();
+        let regions = label(&grid, |a, b| a == b);
+
+        let outer = regions.region_at(Position_isize { x: 0, y: 0 }).unwrap();
+        let b = regions.region_at(Position_isize { x: 1, y: 1 }).unwrap();
+        let x = regions.region_at(Position_isize { x: 4, y: 3 }).unwrap();
+        assert_eq!(outer.holes, 2);
+        assert_eq!(outer.encloses, vec![b.id, x.id]);
+        assert_eq!(outer.sides, 4 + 6 + 4);
+        assert_eq!(b.holes, 0);
+        assert!(b.encloses.is_empty());
+
+        // The inner "A" is a region of its own, so it sits in a hole of both the ring and the
+        // outer "A".
+        let grid = Parser::from_str("AAAAA\nABBBA\nABABA\nABBBA\nAAAAA").as_grid();
+        let regions = label(&grid, |a, b| a == b);
+        assert_eq!(regions.regions.len(), 3);
+        assert_eq!(regions.regions[1].encloses, vec![2]);
+        assert_eq!(regions.regions[0].encloses, vec![1, 2]);
+    }
+}
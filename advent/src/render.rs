@@ -0,0 +1,222 @@
+use std::fmt::{self, Display, Write};
+
+use crate::{grid::Grid, maze::Maze, position::Position_isize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+impl Color {
+    fn ansi_code(self) -> u8 {
+        match self {
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+        }
+    }
+}
+
+// Text picture of a 2D map. Overlays are applied in the order they are added, so later layers
+// are drawn on top of earlier ones. Positions outside the canvas are silently dropped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Canvas {
+    width: usize,
+    height: usize,
+    cells: Vec<(char, Option<Color>)>,
+    color: bool,
+    ruler: bool,
+}
+
+impl Canvas {
+    pub fn blank(width: usize, height: usize, fill: char) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![(fill, None); width * height],
+            color: false,
+            ruler: false,
+        }
+    }
+
+    pub fn from_grid(grid: &Grid<char>) -> Self {
+        let mut canvas = Self::blank(grid.width(), grid.height(), ' ');
+        for (cell, c) in canvas.cells.iter_mut().zip(grid.cells()) {
+            cell.0 = *c;
+        }
+        canvas
+    }
+
+    // Walls as `#`, markers as their own character and everything else as `.`.
+    pub fn from_maze(maze: &Maze) -> Self {
+        let mut canvas = Self::blank(maze.width, maze.height, '.').overlay(&maze.walls, '#');
+        for (c, positions) in &maze.special_chars {
+            canvas = canvas.overlay(positions, *c);
+        }
+        canvas
+    }
+
+    // Sized to fit every position with a non-negative x and y, drawn as `c` on a `.` background.
+    pub fn from_positions<'a>(
+        positions: impl IntoIterator<Item = &'a Position_isize> + Clone,
+        c: char,
+    ) -> Self {
+        let (width, height) = positions.clone().into_iter().fold((0, 0), |(w, h), p| {
+            (
+                w.max((p.x + 1).max(0) as usize),
+                h.max((p.y + 1).max(0) as usize),
+            )
+        });
+
+        Self::blank(width, height, '.').overlay(positions, c)
+    }
+
+    pub fn color(mut self, enabled: bool) -> Self {
+        self.color = enabled;
+        self
+    }
+
+    pub fn ruler(mut self, enabled: bool) -> Self {
+        self.ruler = enabled;
+        self
+    }
+
+    pub fn overlay<'a>(
+        self,
+        positions: impl IntoIterator<Item = &'a Position_isize>,
+        c: char,
+    ) -> Self {
+        self.draw(positions, c, None)
+    }
+
+    pub fn overlay_colored<'a>(
+        self,
+        positions: impl IntoIterator<Item = &'a Position_isize>,
+        c: char,
+        color: Color,
+    ) -> Self {
+        self.draw(positions, c, Some(color))
+    }
+
+    fn draw<'a>(
+        mut self,
+        positions: impl IntoIterator<Item = &'a Position_isize>,
+        c: char,
+        color: Option<Color>,
+    ) -> Self {
+        for p in positions {
+            if p.x < 0 || p.y < 0 || p.x as usize >= self.width || p.y as usize >= self.height {
+                continue;
+            }
+            self.cells[p.y as usize * self.width + p.x as usize] = (c, color);
+        }
+        self
+    }
+
+    fn write_ruler(&self, f: &mut fmt::Formatter<'_>, margin: usize) -> fmt::Result {
+        let places = self.width.saturating_sub(1).to_string().len();
+        for place in (0..places).rev() {
+            write!(f, "{:margin$}", "")?;
+            for x in 0..self.width {
+                let digits = x.to_string();
+                let c = if place < digits.len() {
+                    digits.as_bytes()[digits.len() - 1 - place] as char
+                } else {
+                    ' '
+                };
+                f.write_char(c)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Canvas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label_width = self.height.saturating_sub(1).to_string().len();
+        if self.ruler {
+            self.write_ruler(f, label_width + 1)?;
+        }
+
+        for y in 0..self.height {
+            if self.ruler {
+                write!(f, "{:>label_width$} ", y)?;
+            }
+            for (c, color) in &self.cells[y * self.width..(y + 1) * self.width] {
+                match color {
+                    Some(color) if self.color => {
+                        write!(f, "\x1b[{}m{}\x1b[0m", color.ansi_code(), c)?
+                    }
+                    _ => f.write_char(*c)?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::parse::Parser;
+
+    const MAZE: &str = "#####\n#S..#\n#.#E#\n#####";
+
+    #[test]
+    fn test_round_trip() {
+        let parser = Parser::from_str(MAZE);
+
+        assert_eq!(
+            Canvas::from_grid(&parser.as_grid()).to_string(),
+            MAZE.to_string() + "\n"
+        );
+        assert_eq!(
+            Canvas::from_maze(&parser.as_maze()).to_string(),
+            MAZE.to_string() + "\n"
+        );
+    }
+
+    #[test]
+    fn test_overlays() {
+        let maze = Parser::from_str(MAZE).as_maze();
+        let path = [(1, 1), (2, 1), (3, 1), (3, 2)].map(|(x, y)| Position_isize { x, y });
+        let robot = Position_isize { x: 2, y: 1 };
+
+        let canvas = Canvas::from_maze(&maze)
+            .overlay(&path, 'O')
+            .overlay_colored([&robot], '@', Color::Red);
+        assert_eq!(canvas.to_string(), "#####\n#O@O#\n#.#O#\n#####\n");
+        assert_eq!(
+            canvas.color(true).to_string().lines().nth(1),
+            Some("#O\x1b[31m@\x1b[0mO#")
+        );
+    }
+
+    #[test]
+    fn test_positions_with_ruler() {
+        let positions = HashSet::from([
+            Position_isize { x: 0, y: 0 },
+            Position_isize { x: 11, y: 1 },
+            Position_isize { x: -3, y: 1 },
+        ]);
+
+        assert_eq!(
+            Canvas::from_positions(&positions, '#')
+                .ruler(true)
+                .to_string(),
+            "            11\n  012345678901\n0 #...........\n1 ...........#\n"
+        );
+    }
+}
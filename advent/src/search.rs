@@ -0,0 +1,510 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet},
+    hash::Hash,
+    ops::Add,
+};
+
+use num_traits::Zero;
+
+// Outcome of a search. `distances` holds every state that was settled before the search stopped,
+// so passing `|_| false` as the goal gives the distance to everything reachable.
+#[derive(Debug, Clone)]
+pub struct Search<S, C> {
+    pub distances: HashMap<S, C>,
+    pub goal: Option<S>,
+    parents: HashMap<S, S>,
+}
+
+impl<S: Hash + Eq + Clone, C: Copy> Search<S, C> {
+    pub fn cost(&self) -> Option<C> {
+        self.goal.as_ref().map(|goal| self.distances[goal])
+    }
+
+    // States from the start to the goal, both included.
+    pub fn path(&self) -> Option<Vec<S>> {
+        self.path_to(self.goal.as_ref()?)
+    }
+
+    pub fn path_to(&self, state: &S) -> Option<Vec<S>> {
+        if !self.distances.contains_key(state) {
+            return None;
+        }
+
+        let mut path = vec![state.clone()];
+        while let Some(parent) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+// Heap entry ordered so that the smallest priority is popped first. States don't need to be `Ord`.
+struct Entry<S, C> {
+    priority: C,
+    cost: C,
+    state: S,
+    parent: Option<S>,
+}
+
+impl<S, C: Ord> PartialEq for Entry<S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<S, C: Ord> Eq for Entry<S, C> {}
+
+impl<S, C: Ord> PartialOrd for Entry<S, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S, C: Ord> Ord for Entry<S, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+pub fn dijkstra<S, C, I>(
+    start: S,
+    neighbors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Search<S, C>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Zero + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(start, neighbors, is_goal, |_| C::zero())
+}
+
+// The heuristic must never overestimate the remaining cost and must be consistent, otherwise the
+// returned cost may not be optimal.
+pub fn astar<S, C, I>(
+    start: S,
+    mut neighbors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+    mut heuristic: impl FnMut(&S) -> C,
+) -> Search<S, C>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Zero + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut search = Search {
+        distances: HashMap::new(),
+        goal: None,
+        parents: HashMap::new(),
+    };
+    let mut best = HashMap::new();
+
+    let mut to_process = BinaryHeap::new();
+    best.insert(start.clone(), C::zero());
+    to_process.push(Entry {
+        priority: heuristic(&start),
+        cost: C::zero(),
+        state: start,
+        parent: None,
+    });
+
+    while let Some(Entry {
+        cost,
+        state,
+        parent,
+        ..
+    }) = to_process.pop()
+    {
+        if search.distances.contains_key(&state) {
+            continue;
+        }
+        search.distances.insert(state.clone(), cost);
+        if let Some(parent) = parent {
+            search.parents.insert(state.clone(), parent);
+        }
+
+        if is_goal(&state) {
+            search.goal = Some(state);
+            break;
+        }
+
+        for (next, step) in neighbors(&state) {
+            let next_cost = cost + step;
+            if search.distances.contains_key(&next)
+                || best.get(&next).is_some_and(|known| *known <= next_cost)
+            {
+                continue;
+            }
+
+            best.insert(next.clone(), next_cost);
+            to_process.push(Entry {
+                priority: next_cost + heuristic(&next),
+                cost: next_cost,
+                state: next,
+                parent: Some(state.clone()),
+            });
+        }
+    }
+
+    search
+}
+
+// Like `Search`, but keeps every equal-cost predecessor of each state instead of a single parent,
+// which describes all optimal paths at once as a DAG. `goals` holds each goal state reached at the
+// optimal cost.
+#[derive(Debug, Clone)]
+pub struct OptimalPaths<S, C> {
+    pub distances: HashMap<S, C>,
+    pub goals: Vec<S>,
+    predecessors: HashMap<S, Vec<S>>,
+}
+
+impl<S: Hash + Eq + Clone, C: Copy + Ord> OptimalPaths<S, C> {
+    pub fn cost(&self) -> Option<C> {
+        self.goals.first().map(|goal| self.distances[goal])
+    }
+
+    pub fn predecessors(&self, state: &S) -> &[S] {
+        self.predecessors.get(state).map_or(&[], Vec::as_slice)
+    }
+
+    // Every state that lies on at least one optimal path.
+    pub fn optimal_path_cells(&self) -> HashSet<S> {
+        let mut cells = HashSet::new();
+        let mut to_visit = self.goals.clone();
+
+        while let Some(state) = to_visit.pop() {
+            if cells.insert(state.clone()) {
+                to_visit.extend(self.predecessors(&state).iter().cloned());
+            }
+        }
+
+        cells
+    }
+
+    pub fn count_optimal_paths(&self) -> u128 {
+        let mut states = self.optimal_path_cells().into_iter().collect::<Vec<_>>();
+        states.sort_by_key(|state| self.distances[state]);
+
+        // Predecessors are always strictly closer to the start, so one pass in distance order
+        // sees every predecessor before the states it leads to.
+        let mut counts: HashMap<&S, u128> = HashMap::new();
+        for state in &states {
+            let predecessors = self.predecessors(state);
+            let count = if predecessors.is_empty() {
+                1
+            } else {
+                predecessors.iter().map(|p| counts[p]).sum()
+            };
+            counts.insert(state, count);
+        }
+
+        self.goals.iter().map(|goal| counts[goal]).sum()
+    }
+
+    // Yields each optimal path from the start to a goal, one at a time, without building them all.
+    pub fn optimal_paths(&self) -> impl Iterator<Item = Vec<S>> + '_ {
+        let mut goals = self.goals.iter();
+        // The path walked back from a goal so far, with the index of the next predecessor to try.
+        let mut stack: Vec<(&S, usize)> = Vec::new();
+
+        std::iter::from_fn(move || loop {
+            let Some(&(state, next)) = stack.last() else {
+                stack.push((goals.next()?, 0));
+                continue;
+            };
+
+            let predecessors = self.predecessors(state);
+            if predecessors.is_empty() {
+                let path = stack.iter().rev().map(|(s, _)| (*s).clone()).collect();
+                stack.pop();
+                return Some(path);
+            }
+
+            if next < predecessors.len() {
+                stack.last_mut().unwrap().1 += 1;
+                stack.push((&predecessors[next], 0));
+            } else {
+                stack.pop();
+            }
+        })
+    }
+}
+
+// Runs Dijkstra past the first goal until every optimal path to a goal is known. Panics on a step
+// cost that isn't positive, which could make the predecessor graph cyclic.
+pub fn dijkstra_all<S, C, I>(
+    start: S,
+    mut neighbors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> OptimalPaths<S, C>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Zero + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut paths = OptimalPaths {
+        distances: HashMap::new(),
+        goals: Vec::new(),
+        predecessors: HashMap::new(),
+    };
+    let mut best = HashMap::new();
+    let mut goal_cost = None;
+
+    let mut to_process = BinaryHeap::new();
+    best.insert(start.clone(), C::zero());
+    to_process.push(Entry {
+        priority: C::zero(),
+        cost: C::zero(),
+        state: start,
+        parent: None,
+    });
+
+    while let Some(Entry { cost, state, .. }) = to_process.pop() {
+        if goal_cost.is_some_and(|goal_cost| cost > goal_cost) {
+            break;
+        }
+        if paths.distances.contains_key(&state) {
+            continue;
+        }
+        paths.distances.insert(state.clone(), cost);
+
+        if is_goal(&state) {
+            goal_cost = Some(cost);
+            paths.goals.push(state);
+            continue;
+        }
+
+        for (next, step) in neighbors(&state) {
+            assert!(step > C::zero(), "dijkstra_all needs positive step costs");
+            let next_cost = cost + step;
+            if paths.distances.contains_key(&next) {
+                continue;
+            }
+
+            match best.get(&next).map(|known| next_cost.cmp(known)) {
+                Some(Ordering::Greater) => continue,
+                Some(Ordering::Equal) => {
+                    // The same edge can be yielded twice; it is still only one way in.
+                    let predecessors = paths.predecessors.entry(next).or_default();
+                    if !predecessors.contains(&state) {
+                        predecessors.push(state.clone());
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+
+            best.insert(next.clone(), next_cost);
+            paths.predecessors.insert(next.clone(), vec![state.clone()]);
+            to_process.push(Entry {
+                priority: next_cost,
+                cost: next_cost,
+                state: next,
+                parent: None,
+            });
+        }
+    }
+
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        parse::Parser,
+        position::{Direction, Position_isize},
+    };
+
+    const MAZE: &str = "\
+###############
+#.......#....E#
+#.#.###.#.###.#
+#.....#.#...#.#
+#.###.#####.#.#
+#.#.#.......#.#
+#.#.#####.###.#
+#...........#.#
+###.#.#####.#.#
+#...#.....#.#.#
+#.#.#.###.#.#.#
+#.....#...#.#.#
+#.###.#.#.#.#.#
+#S..#.....#...#
+###############";
+
+    #[test]
+    fn test_dijkstra_with_turns() {
+        let maze = Parser::from_str(MAZE).as_maze();
+        let start = maze.get_only_position('S').unwrap();
+        let exit = maze.get_only_position('E').unwrap();
+
+        let search = dijkstra(
+            (start, Direction::East),
+            |&(pos, dir)| {
+                let mut next = vec![
+                    ((pos, dir.turn_left()), 1000),
+                    ((pos, dir.turn_right()), 1000),
+                ];
+                if maze.is_open(pos + dir) {
+                    next.push(((pos + dir, dir), 1));
+                }
+                next
+            },
+            |(pos, _)| *pos == exit,
+        );
+
+        assert_eq!(search.cost(), Some(7036));
+        let path = search.path().unwrap();
+        assert_eq!(path.first(), Some(&(start, Direction::East)));
+        assert_eq!(path.last().map(|(pos, _)| *pos), Some(exit));
+    }
+
+    #[test]
+    fn test_all_optimal_paths() {
+        let maze = Parser::from_str(MAZE).as_maze();
+        let start = maze.get_only_position('S').unwrap();
+        let exit = maze.get_only_position('E').unwrap();
+
+        let paths = dijkstra_all(
+            (start, Direction::East),
+            |&(pos, dir)| {
+                let mut next = vec![
+                    ((pos, dir.turn_left()), 1000),
+                    ((pos, dir.turn_right()), 1000),
+                ];
+                if maze.is_open(pos + dir) {
+                    next.push(((pos + dir, dir), 1));
+                }
+                next
+            },
+            |(pos, _)| *pos == exit,
+        );
+
+        assert_eq!(paths.cost(), Some(7036));
+        let tiles = paths
+            .optimal_path_cells()
+            .into_iter()
+            .map(|(pos, _)| pos)
+            .collect::<HashSet<_>>();
+        assert_eq!(tiles.len(), 45);
+
+        assert_eq!(paths.count_optimal_paths(), 3);
+        let all = paths.optimal_paths().collect::<Vec<_>>();
+        assert_eq!(all.len(), 3);
+        assert!(all.iter().all(|path| path[0] == (start, Direction::East)));
+        assert_eq!(
+            all.iter()
+                .flatten()
+                .map(|(pos, _)| *pos)
+                .collect::<HashSet<_>>(),
+            tiles
+        );
+    }
+
+    #[test]
+    fn test_counts_paths_in_open_grid() {
+        // Every monotone path across an open 5x5 grid is optimal: C(8, 4) of them.
+        let bounds = crate::position::Rect::from_size(5, 5);
+        let corner = Position_isize { x: 4, y: 4 };
+
+        let paths = dijkstra_all(
+            Position_isize { x: 0, y: 0 },
+            |pos| {
+                pos.neighbors4_in(bounds)
+                    .map(|next| (next, 1u32))
+                    .collect::<Vec<_>>()
+            },
+            |pos| *pos == corner,
+        );
+
+        assert_eq!(paths.cost(), Some(8));
+        assert_eq!(paths.count_optimal_paths(), 70);
+        assert_eq!(paths.optimal_paths().count(), 70);
+        assert_eq!(paths.optimal_path_cells().len(), 25);
+    }
+
+    #[test]
+    fn test_duplicate_edges() {
+        // Both routes to 3 are listed twice, but there are still only two paths.
+        let paths = dijkstra_all(
+            0,
+            |n| match n {
+                0 => vec![(1, 1), (2, 1), (1, 1)],
+                1 | 2 => vec![(3, 1), (3, 1)],
+                _ => vec![],
+            },
+            |n| *n == 3,
+        );
+
+        assert_eq!(paths.cost(), Some(2));
+        assert_eq!(paths.count_optimal_paths(), 2);
+        assert_eq!(paths.optimal_paths().count(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "dijkstra_all needs positive step costs")]
+    fn test_zero_cost_step() {
+        dijkstra_all(0, |n| vec![((n + 1) % 3, 0)], |n| *n == 5);
+    }
+
+    #[test]
+    fn test_astar_matches_dijkstra() {
+        let maze = Parser::from_str(MAZE).as_maze();
+        let start = maze.get_only_position('S').unwrap();
+        let exit = maze.get_only_position('E').unwrap();
+        let neighbors = |pos: &Position_isize| {
+            pos.neighbors4_in(maze.bounds())
+                .filter(|next| maze.is_open(*next))
+                .map(|next| (next, 1))
+                .collect::<Vec<_>>()
+        };
+
+        let plain = dijkstra(start, neighbors, |pos| *pos == exit);
+        let guided = astar(
+            start,
+            neighbors,
+            |pos| *pos == exit,
+            |pos| pos.manhattan(exit),
+        );
+
+        assert_eq!(plain.cost(), Some(28));
+        assert_eq!(guided.cost(), plain.cost());
+        assert_eq!(guided.path().unwrap().len(), 29);
+        assert!(guided.distances.len() <= plain.distances.len());
+    }
+
+    #[test]
+    fn test_full_distance_map() {
+        let maze = Parser::from_str("#####\n#S..#\n#.#.#\n#####").as_maze();
+        let start = maze.get_only_position('S').unwrap();
+
+        let search = dijkstra(
+            start,
+            |pos| {
+                pos.neighbors4_in(maze.bounds())
+                    .filter(|next| maze.is_open(*next))
+                    .map(|next| (next, 1))
+                    .collect::<Vec<_>>()
+            },
+            |_| false,
+        );
+
+        assert_eq!(search.goal, None);
+        assert_eq!(search.cost(), None);
+        assert_eq!(search.distances.len(), 5);
+        assert_eq!(search.distances[&Position_isize { x: 3, y: 2 }], 3);
+        assert_eq!(
+            search.path_to(&Position_isize { x: 3, y: 1 }),
+            Some(vec![
+                start,
+                Position_isize { x: 2, y: 1 },
+                Position_isize { x: 3, y: 1 }
+            ])
+        );
+        assert_eq!(search.path_to(&Position_isize { x: 0, y: 0 }), None);
+    }
+}
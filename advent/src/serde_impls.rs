@@ -0,0 +1,147 @@
+// Serialized forms are meant to be stable enough for golden files. A `Position` is the string
+// "x,y", so maps keyed by positions become JSON objects keyed by "x,y". A `Maze` lists its
+// cells sorted in reading order instead of in hash order.
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    maze::Maze,
+    position::{Position, Position_isize},
+};
+
+impl<T: Display> Serialize for Position<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{},{}", self.x, self.y))
+    }
+}
+
+impl<'de, T: FromStr> Deserialize<'de> for Position<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        let invalid =
+            || de::Error::invalid_value(de::Unexpected::Str(&text), &"a position \"x,y\"");
+
+        let (x, y) = text.split_once(',').ok_or_else(invalid)?;
+        Ok(Position {
+            x: x.trim().parse().map_err(|_| invalid())?,
+            y: y.trim().parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct MazeRepr {
+    width: usize,
+    height: usize,
+    walls: Vec<Position_isize>,
+    special_chars: BTreeMap<char, Vec<Position_isize>>,
+}
+
+fn reading_order(positions: impl IntoIterator<Item = Position_isize>) -> Vec<Position_isize> {
+    let mut positions = positions.into_iter().collect::<Vec<_>>();
+    positions.sort_by_key(|p| (p.y, p.x));
+    positions
+}
+
+impl Serialize for Maze {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        MazeRepr {
+            width: self.width,
+            height: self.height,
+            walls: reading_order(self.walls.iter().copied()),
+            special_chars: self
+                .special_chars
+                .iter()
+                .map(|(c, positions)| (*c, reading_order(positions.iter().copied())))
+                .collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Maze {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = MazeRepr::deserialize(deserializer)?;
+
+        let mut maze = Maze::new(repr.width, repr.height, repr.walls.into_iter().collect());
+        maze.special_chars = repr
+            .special_chars
+            .into_iter()
+            .map(|(c, positions)| (c, positions.into_iter().collect()))
+            .collect();
+        Ok(maze)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    use super::*;
+    use crate::{
+        grid::Grid,
+        parse::Parser,
+        position::{Direction, Rect, Torus},
+    };
+
+    #[test]
+    fn test_position() {
+        let distances = HashMap::from([(Position_isize { x: 3, y: -1 }, 7)]);
+
+        assert_eq!(serde_json::to_string(&distances).unwrap(), r#"{"3,-1":7}"#);
+        assert_eq!(
+            serde_json::from_str::<HashMap<Position_isize, i32>>(r#"{"3,-1":7}"#).unwrap(),
+            distances
+        );
+        assert!(serde_json::from_str::<Position_isize>(r#""3;-1""#).is_err());
+        assert!(serde_json::from_str::<Position<usize>>(r#""3,-1""#).is_err());
+    }
+
+    #[test]
+    fn test_maze_is_stable() {
+        let maze = Parser::from_str("####\n#S.#\n#.E#\n####").as_maze();
+        let json = serde_json::to_string(&maze).unwrap();
+
+        assert!(
+            json.starts_with(r#"{"width":4,"height":4,"walls":["0,0","1,0","2,0","3,0","0,1","#)
+        );
+        assert!(json.ends_with(r#""special_chars":{"E":["2,2"],"S":["1,1"]}}"#));
+
+        let parsed = serde_json::from_str::<Maze>(&json).unwrap();
+        assert_eq!(parsed.walls, maze.walls);
+        assert_eq!(parsed.special_chars, maze.special_chars);
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+    }
+
+    #[test]
+    fn test_grid_types() {
+        let grid = Grid::from_rows(vec![vec![1, 2], vec![3, 4]]);
+        let json = serde_json::to_string(&grid).unwrap();
+
+        assert_eq!(json, r#"{"width":2,"height":2,"cells":[1,2,3,4]}"#);
+        assert_eq!(serde_json::from_str::<Grid<i32>>(&json).unwrap(), grid);
+        assert!(
+            serde_json::from_str::<Grid<i32>>(r#"{"width":2,"height":2,"cells":[1]}"#).is_err()
+        );
+        assert!(serde_json::from_str::<Grid<i32>>(
+            r#"{"width":18446744073709551615,"height":2,"cells":[]}"#
+        )
+        .is_err());
+
+        let torus = Torus::new(11, 7);
+        let json = serde_json::to_string(&torus).unwrap();
+        assert_eq!(json, r#"{"width":11,"height":7}"#);
+        assert_eq!(serde_json::from_str::<Torus>(&json).unwrap(), torus);
+        assert!(serde_json::from_str::<Torus>(r#"{"width":0,"height":7}"#).is_err());
+
+        let rect = Rect::from_size(3i64, 2);
+        assert_eq!(
+            serde_json::to_string(&rect).unwrap(),
+            r#"{"min":"0,0","max":"3,2"}"#
+        );
+
+        let path = BTreeMap::from([(Position_isize { x: 0, y: 0 }, Direction::East)]);
+        assert_eq!(serde_json::to_string(&path).unwrap(), r#"{"0,0":"East"}"#);
+    }
+}
@@ -0,0 +1,178 @@
+use crate::position::{Position_isize, Rect};
+
+// Disjoint sets over `0..len` with path compression and union by size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl UnionFind {
+    pub fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        let mut x = x;
+        while self.parent[x] != root {
+            let next = self.parent[x];
+            self.parent[x] = root;
+            x = next;
+        }
+
+        root
+    }
+
+    // Returns false if `a` and `b` were already in the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        let (big, small) = if self.size[a] >= self.size[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parent[small] = big;
+        self.size[big] += self.size[small];
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    pub fn set_size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+}
+
+// Obstacles land one at a time inside `bounds`. Returns the index of the first one after which
+// `start` can no longer reach `goal` with 4-way moves, or `None` if they stay connected.
+//
+// Works backwards: start with every obstacle in place and lift them in reverse order, so each
+// lift only merges sets and the whole run is near-linear.
+pub fn first_blocking_obstacle(
+    bounds: Rect<isize>,
+    obstacles: &[Position_isize],
+    start: Position_isize,
+    goal: Position_isize,
+) -> Option<usize> {
+    let width = bounds.width() as usize;
+    let index_of = |pos: Position_isize| {
+        ((pos.y - bounds.min.y) as usize) * width + (pos.x - bounds.min.x) as usize
+    };
+
+    // The earliest obstacle at each cell. Later duplicates don't change anything.
+    let mut blocked_by = vec![None; width * bounds.height() as usize];
+    for (i, obstacle) in obstacles.iter().enumerate() {
+        if bounds.contains(*obstacle) {
+            blocked_by[index_of(*obstacle)].get_or_insert(i);
+        }
+    }
+
+    let mut sets = UnionFind::new(blocked_by.len());
+    let open = |sets: &mut UnionFind, blocked_by: &[Option<usize>], pos: Position_isize| {
+        for next in pos.neighbors4_in(bounds) {
+            if blocked_by[index_of(next)].is_none() {
+                sets.union(index_of(pos), index_of(next));
+            }
+        }
+    };
+
+    for pos in bounds.iter() {
+        if blocked_by[index_of(pos)].is_none() {
+            open(&mut sets, &blocked_by, pos);
+        }
+    }
+
+    let connected = |sets: &mut UnionFind, blocked_by: &[Option<usize>]| {
+        blocked_by[index_of(start)].is_none()
+            && blocked_by[index_of(goal)].is_none()
+            && sets.connected(index_of(start), index_of(goal))
+    };
+    if !bounds.contains(start) || !bounds.contains(goal) || connected(&mut sets, &blocked_by) {
+        return None;
+    }
+
+    for (i, obstacle) in obstacles.iter().enumerate().rev() {
+        if !bounds.contains(*obstacle) || blocked_by[index_of(*obstacle)] != Some(i) {
+            continue;
+        }
+
+        blocked_by[index_of(*obstacle)] = None;
+        open(&mut sets, &blocked_by, *obstacle);
+        if connected(&mut sets, &blocked_by) {
+            return Some(i);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut sets = UnionFind::new(6);
+
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert!(sets.connected(0, 3));
+        assert!(!sets.connected(0, 4));
+        assert_eq!(sets.set_size(2), 4);
+        assert_eq!(sets.set_size(5), 1);
+        assert_eq!(sets.len(), 6);
+    }
+
+    #[test]
+    fn test_first_blocking_obstacle() {
+        // The example from 2024 day 18 on a 7x7 grid.
+        let bytes = "5,4 4,2 4,5 3,0 2,1 6,3 2,4 1,5 0,6 3,3 2,6 5,1 1,2 5,5 2,5 6,5 1,4 0,4 6,4 \
+                     1,1 6,1 1,0 0,5 1,6 2,0"
+            .split_whitespace()
+            .map(|b| {
+                let (x, y) = b.split_once(',').unwrap();
+                Position_isize {
+                    x: x.parse().unwrap(),
+                    y: y.parse().unwrap(),
+                }
+            })
+            .collect::<Vec<_>>();
+        let bounds = Rect::from_size(7, 7);
+        let start = Position_isize { x: 0, y: 0 };
+        let goal = Position_isize { x: 6, y: 6 };
+
+        let first = first_blocking_obstacle(bounds, &bytes, start, goal);
+        assert_eq!(first, Some(20));
+        assert_eq!(bytes[20], Position_isize { x: 6, y: 1 });
+
+        assert_eq!(
+            first_blocking_obstacle(bounds, &bytes[..20], start, goal),
+            None
+        );
+    }
+}
@@ -1,9 +1,6 @@
+use advent::parse::{ParseError, Parser as AdventParser};
 use clap::Parser;
 use itertools::Itertools;
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -22,7 +19,7 @@ fn main() {
         args.data_file
     };
 
-    let (left, right) = parse(&data_file);
+    let (left, right) = AdventParser::parse_or_exit(&data_file, parse);
 
     let result1 = part1(left.clone(), right.clone());
     println!("Part1: {}", result1);
@@ -50,24 +47,18 @@ fn part2(left: Vec<i64>, right: Vec<i64>) -> i64 {
         .sum::<i64>()
 }
 
-fn parse(file: &str) -> (Vec<i64>, Vec<i64>) {
-    let file = File::open(file).expect("Failed to open file");
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .map(|line| line.expect("Failed to read line"))
-        .collect();
-
-    lines
-        .iter()
-        .map(|line| {
-            let (a, b) = line.split_ascii_whitespace().collect_tuple().unwrap();
-            (
-                a.parse::<i64>().expect("Failed to parse"),
-                b.parse::<i64>().expect("Failed to parse"),
-            )
-        })
-        .unzip()
+fn parse(parser: &AdventParser) -> Result<(Vec<i64>, Vec<i64>), ParseError> {
+    Ok(parser
+        .try_parse_lines(|line| {
+            let (a, b) = line
+                .text()
+                .split_ascii_whitespace()
+                .collect_tuple()
+                .ok_or_else(|| line.error(line.text(), "expected two numbers"))?;
+            Ok((line.parse::<i64>(a)?, line.parse::<i64>(b)?))
+        })?
+        .into_iter()
+        .unzip())
 }
 
 #[cfg(test)]
@@ -76,14 +67,20 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let (left, right) = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let (left, right) = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result1 = part1(left, right);
         assert_eq!(result1, 11);
     }
 
     #[test]
     fn test_part2() {
-        let (left, right) = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let (left, right) = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result2 = part2(left, right);
 
         assert_eq!(result2, 31);
@@ -1,9 +1,6 @@
+use advent::parse::{ParseError, Parser as AdventParser};
 use clap::Parser;
 use itertools::Itertools;
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -22,7 +19,7 @@ fn main() {
         args.data_file
     };
 
-    let data = parse(&data_file);
+    let data = AdventParser::parse_or_exit(&data_file, parse);
 
     let result1 = part1(data.clone());
     println!("Part1: {}", result1);
@@ -63,22 +60,8 @@ fn is_safe(report: &Vec<i64>) -> bool {
     })
 }
 
-fn parse(file: &str) -> Vec<Vec<i64>> {
-    let file = File::open(file).expect("Failed to open file");
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .map(|line| line.expect("Failed to read line"))
-        .collect();
-
-    lines
-        .iter()
-        .map(|line| {
-            line.split_ascii_whitespace()
-                .map(|v| v.parse::<i64>().unwrap())
-                .collect_vec()
-        })
-        .collect_vec()
+fn parse(parser: &AdventParser) -> Result<Vec<Vec<i64>>, ParseError> {
+    parser.try_parse_lines(|line| line.whitespace_fields())
 }
 
 #[cfg(test)]
@@ -87,14 +70,20 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let data = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let data = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result1 = part1(data);
         assert_eq!(result1, 2);
     }
 
     #[test]
     fn test_part2() {
-        let data = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let data = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result2 = part2(data);
 
         assert_eq!(result2, 4);
@@ -1,10 +1,7 @@
+use advent::parse::{ParseError, Parser as AdventParser};
 use clap::Parser;
 use itertools::Itertools;
 use regex::Regex;
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -23,7 +20,7 @@ fn main() {
         args.data_file
     };
 
-    let data = parse(&data_file);
+    let data = AdventParser::parse_or_exit(&data_file, parse);
 
     let result1 = part1(data.clone());
     println!("Part1: {}", result1);
@@ -71,13 +68,12 @@ fn part2(data: Vec<char>) -> i64 {
     sum
 }
 
-fn parse(file: &str) -> Vec<char> {
-    let file = File::open(file).expect("Failed to open file");
-    let reader = BufReader::new(file);
-    reader
-        .lines()
-        .flat_map(|line| line.expect("Failed to read line").chars().collect_vec())
-        .collect_vec()
+fn parse(parser: &AdventParser) -> Result<Vec<char>, ParseError> {
+    Ok(parser
+        .try_get_lines()?
+        .iter()
+        .flat_map(|line| line.chars())
+        .collect_vec())
 }
 
 #[cfg(test)]
@@ -86,7 +82,10 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let data = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let data = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result1 = part1(data);
 
         assert_eq!(result1, 161);
@@ -94,7 +93,10 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        let data = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test2.txt"));
+        let data = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test2.txt"),
+        ))
+        .unwrap();
         let result2 = part2(data);
 
         assert_eq!(result2, 48);
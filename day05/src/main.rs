@@ -1,10 +1,6 @@
+use advent::graph::{Cycle, PartialOrder};
+use advent::parse::{ParseError, Parser as AdventParser};
 use clap::Parser;
-use itertools::Itertools;
-use std::{
-    collections::HashSet,
-    fs::File,
-    io::{BufRead, BufReader},
-};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -17,7 +13,7 @@ struct Args {
 
 #[derive(Debug, Clone)]
 struct Data {
-    rules: Vec<(i64, i64)>,
+    rules: PartialOrder<i64>,
     prints: Vec<Vec<i64>>,
 }
 
@@ -29,125 +25,57 @@ fn main() {
         args.data_file
     };
 
-    let data = parse(&data_file);
+    let data = AdventParser::parse_or_exit(&data_file, parse);
 
     let result1 = part1(data.clone());
     println!("Part1: {}", result1);
 
-    println!("Part 2: {}", part2(data))
+    match part2(data) {
+        Ok(result2) => println!("Part 2: {}", result2),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
 }
 
 fn part1(data: Data) -> i64 {
     data.prints
         .iter()
-        .filter(|pages| is_valid_print(pages, &data.rules))
+        .filter(|pages| data.rules.is_sorted(pages))
         .map(|pages| pages.get(pages.len() / 2).unwrap())
         .sum::<i64>()
 }
 
-fn is_valid_print(pages: &Vec<i64>, rules: &Vec<(i64, i64)>) -> bool {
-    let page_numbers: HashSet<i64> = HashSet::from_iter(pages.iter().cloned());
-    let relevant_rules = rules
+// Rules that contradict each other on one of the updates leave it with no correct order.
+fn part2(data: Data) -> Result<i64, Cycle<i64>> {
+    data.prints
         .iter()
-        .filter(|(from, to)| page_numbers.contains(from) && page_numbers.contains(to))
-        .collect_vec();
-
-    is_valid_print_with_rules(pages, &relevant_rules)
-}
-
-fn is_valid_print_with_rules(pages: &Vec<i64>, relevant_rules: &Vec<&(i64, i64)>) -> bool {
-    relevant_rules.iter().all(|(first, second)| {
-        pages
-            .iter()
-            .enumerate()
-            .find(|(_, p)| **p == *first)
-            .unwrap()
-            .0
-            < pages
-                .iter()
-                .enumerate()
-                .find(|(_, p)| **p == *second)
-                .unwrap()
-                .0
-    })
+        .filter(|pages| !data.rules.is_sorted(pages))
+        .map(|pages| {
+            data.rules
+                .sort(pages)
+                .map(|sorted| sorted[sorted.len() / 2])
+        })
+        .sum()
 }
 
-fn correctly_order(mut pages: Vec<i64>, rules: &Vec<(i64, i64)>) -> Option<Vec<i64>> {
-    let page_numbers: HashSet<i64> = HashSet::from_iter(pages.iter().cloned());
-    let relevant_rules = rules
-        .iter()
-        .filter(|(from, to)| page_numbers.contains(from) && page_numbers.contains(to))
-        .collect_vec();
-
-    if is_valid_print_with_rules(&pages, &relevant_rules) {
-        return None;
-    }
-
-    while relevant_rules.iter().any(|(first, second)| {
-        let from = pages
-            .iter()
-            .enumerate()
-            .find(|(_, p)| **p == *first)
-            .unwrap()
-            .0;
-        let to = pages
-            .iter()
-            .enumerate()
-            .find(|(_, p)| **p == *second)
-            .unwrap()
-            .0;
-        if from > to {
-            pages.swap(from, to);
-            return true;
-        }
-
-        false
-    }) {}
-
-    Some(
-        pages
-            .iter()
-            .cloned()
-            .permutations(pages.len())
-            .find(|p| is_valid_print_with_rules(p, &relevant_rules))
-            .unwrap(),
-    )
-}
-
-fn part2(data: Data) -> i64 {
-    data.prints
-        .into_iter()
-        .filter_map(|pages| correctly_order(pages, &data.rules))
-        .map(|pages| pages.get(pages.len() / 2).unwrap().clone())
-        .sum::<i64>()
-}
+fn parse(parser: &AdventParser) -> Result<Data, ParseError> {
+    let mut sections = parser.try_sections()?;
+    let (Some(rules), Some(prints)) = (sections.next(), sections.next()) else {
+        return Err(ParseError::new(
+            parser.source(),
+            "expected ordering rules and updates separated by a blank line",
+        ));
+    };
 
-fn parse(file: &str) -> Data {
-    let file = File::open(file).expect("Failed to open file");
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .map(|line| line.expect("Failed to read line"))
-        .collect();
-
-    Data {
-        rules: lines
-            .iter()
-            .take_while(|line| !line.is_empty())
-            .map(|line| line.split_once('|').unwrap())
-            .map(|(a, b)| (a.parse::<i64>().unwrap(), b.parse::<i64>().unwrap()))
-            .collect_vec(),
-        prints: lines
-            .iter()
-            .skip_while(|line| !line.is_empty())
-            .filter(|line| !line.is_empty())
-            .map(|line| {
-                line.split(',')
-                    .map(|page| page.parse::<i64>().unwrap())
-                    .collect_vec()
-            })
-            .collect_vec(),
-    }
+    Ok(Data {
+        rules: PartialOrder::from_rules(rules.parse_lines(|line| {
+            let (a, b) = line.split_once("|")?;
+            Ok((line.parse(a)?, line.parse(b)?))
+        })?),
+        prints: prints.parse_lines(|line| line.fields(","))?,
+    })
 }
 
 #[cfg(test)]
@@ -156,7 +84,10 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let data = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let data = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result1 = part1(data);
 
         assert_eq!(result1, 143);
@@ -164,9 +95,19 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        let data = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let data = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result2 = part2(data);
 
-        assert_eq!(result2, 123);
+        assert_eq!(result2, Ok(123));
+    }
+
+    #[test]
+    fn test_part2_cycle() {
+        let data = parse(&AdventParser::from_str("1|2\n2|3\n3|1\n\n1,2,3\n")).unwrap();
+
+        assert!(part2(data).is_err());
     }
 }
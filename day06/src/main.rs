@@ -1,11 +1,9 @@
+use advent::cycle;
+use advent::parse::{MazeBuilder, ParseError, Parser as AdventParser};
+use advent::position::{Direction, Position};
 use clap::Parser;
 use itertools::Itertools;
-use std::{
-    collections::HashSet,
-    fs::File,
-    hash::Hash,
-    io::{BufRead, BufReader},
-};
+use std::collections::HashSet;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -16,17 +14,11 @@ struct Args {
     debug: bool,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Position {
-    x: i64,
-    y: i64,
-}
-
 #[derive(Debug, Clone)]
 struct Data {
-    guard: Position,
-    stones: HashSet<Position>,
-    max_position: Position,
+    guard: Position<i64>,
+    stones: HashSet<Position<i64>>,
+    max_position: Position<i64>,
 }
 
 fn main() {
@@ -37,148 +29,151 @@ fn main() {
         args.data_file
     };
 
-    let data = parse(&data_file);
+    let data = AdventParser::parse_or_exit(&data_file, parse);
+
+    match (part1(&data), part2(&data)) {
+        (Some(result1), Some(result2)) => {
+            println!("Part1: {}", result1.len());
+            println!("Part 2: {}", result2)
+        }
+        _ => println!("The guard never leaves the map"),
+    }
+}
 
-    let result1 = part1(&data, None);
-    println!("Part1: {}", result1.unwrap().len());
+// The cells the guard visits before walking off the map, or None if they are stuck in a loop.
+fn part1(data: &Data) -> Option<Vec<Position<i64>>> {
+    if guard_loops(data, None) {
+        return None;
+    }
 
-    println!("Part 2: {}", part2(data))
+    Some(
+        std::iter::successors(Some((data.guard, Direction::North)), |guard| {
+            step(data, None, &Some(*guard))
+        })
+        .map(|(pos, _)| pos)
+        .unique()
+        .collect_vec(),
+    )
 }
 
-fn part1(data: &Data, new_stone: Option<Position>) -> Option<Vec<Position>> {
-    let mut guard_pos = data.guard;
-    let mut guard_velocity = Position { x: 0, y: -1 };
-    let mut visited = HashSet::new();
+// The guard as (position, heading), or None once they have walked off the map.
+fn step(
+    data: &Data,
+    new_stone: Option<Position<i64>>,
+    guard: &Option<(Position<i64>, Direction)>,
+) -> Option<(Position<i64>, Direction)> {
+    let (guard_pos, heading) = (*guard)?;
+
+    let new_guard_position = guard_pos + heading;
+    if data.stones.contains(&new_guard_position) || new_stone == Some(new_guard_position) {
+        return Some((guard_pos, heading.turn_right()));
+    }
 
-    while guard_pos.y >= 0
-        && guard_pos.y <= data.max_position.y
-        && guard_pos.x >= 0
-        && guard_pos.x <= data.max_position.x
+    if new_guard_position.y < 0
+        || new_guard_position.y > data.max_position.y
+        || new_guard_position.x < 0
+        || new_guard_position.x > data.max_position.x
     {
-        if !visited.insert((guard_pos, guard_velocity)) {
-            // Loop found
-            return None;
-        }
+        return None;
+    }
 
-        let mut new_guard_velocity = guard_velocity;
-
-        let mut new_guard_position = Position {
-            x: guard_pos.x + new_guard_velocity.x,
-            y: guard_pos.y + new_guard_velocity.y,
-        };
-        if data.stones.contains(&new_guard_position)
-            || (new_stone.is_some() && new_guard_position == new_stone.unwrap())
-        {
-            new_guard_velocity = Position {
-                x: -guard_velocity.y,
-                y: guard_velocity.x,
-            };
-            new_guard_position = guard_pos;
-        }
+    Some((new_guard_position, heading))
+}
 
-        guard_velocity = new_guard_velocity;
-        guard_pos = new_guard_position;
-    }
+// True if the guard never leaves the map.
+fn guard_loops(data: &Data, new_stone: Option<Position<i64>>) -> bool {
+    let (_, period) = cycle::detect(Some((data.guard, Direction::North)), |guard| {
+        step(data, new_stone, guard)
+    });
 
-    Some(
-        visited
-            .iter()
-            .map(|(pos, _)| pos.clone())
-            .unique()
-            .collect_vec(),
-    )
+    // Walking off the map ends in None, which steps to itself. The guard changes position or
+    // direction on every step, so any longer cycle is a real loop.
+    period > 1
 }
 
-fn part2(data: Data) -> i64 {
-    let possible_new_stones: HashSet<Position> =
-        HashSet::from_iter(part1(&data, None).unwrap().into_iter());
-
-    possible_new_stones
-        .into_iter()
-        .filter(|new_stone| *new_stone != data.guard)
-        .filter(|new_stone| {
-            if part1(&data, Some(new_stone.clone())).is_none() {
-                true
-            } else {
-                false
-            }
-        })
-        .count() as i64
+fn part2(data: &Data) -> Option<i64> {
+    let possible_new_stones: HashSet<Position<i64>> = HashSet::from_iter(part1(data)?);
+
+    Some(
+        possible_new_stones
+            .into_iter()
+            .filter(|new_stone| *new_stone != data.guard)
+            .filter(|new_stone| guard_loops(data, Some(*new_stone)))
+            .count() as i64,
+    )
 }
 
-fn parse(file: &str) -> Data {
-    let file = File::open(file).expect("Failed to open file");
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .map(|line| line.expect("Failed to read line"))
-        .collect();
+fn parse(parser: &AdventParser) -> Result<Data, ParseError> {
+    let maze = parser.try_as_maze_with(&MazeBuilder::new().markers("^"))?;
+    let guard = maze.get_only_position('^')?;
 
-    Data {
-        stones: lines
+    Ok(Data {
+        guard: Position {
+            x: guard.x as i64,
+            y: guard.y as i64,
+        },
+        stones: maze
+            .walls
             .iter()
-            .enumerate()
-            .flat_map(|(y, line)| {
-                line.chars()
-                    .enumerate()
-                    .filter_map(|(x, c)| {
-                        if c == '#' {
-                            Some(Position {
-                                x: x as i64,
-                                y: y as i64,
-                            })
-                        } else {
-                            None
-                        }
-                    })
-                    .collect_vec()
+            .map(|stone| Position {
+                x: stone.x as i64,
+                y: stone.y as i64,
             })
             .collect(),
-        guard: lines
-            .iter()
-            .enumerate()
-            .find_map(|(y, line)| {
-                line.chars()
-                    .enumerate()
-                    .find(|(_, c)| *c == '^')
-                    .map(|(x, _)| Position {
-                        x: x as i64,
-                        y: y as i64,
-                    })
-            })
-            .unwrap(),
         max_position: Position {
-            x: lines.get(0).unwrap().len() as i64 - 1,
-            y: lines.len() as i64 - 1,
+            x: maze.width as i64 - 1,
+            y: maze.height as i64 - 1,
         },
-    }
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "\
+....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...
+";
+
     #[test]
     fn test_part1() {
-        let data = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
-        let result1 = part1(&data, None);
+        let data = parse(&AdventParser::from_str(EXAMPLE)).unwrap();
+        let result1 = part1(&data);
+
+        assert_eq!(result1.map(|path| path.len()), Some(41));
+    }
+
+    #[test]
+    fn test_part1_loop() {
+        let data = parse(&AdventParser::from_str(".#..\n...#\n#^..\n..#.\n")).unwrap();
 
-        assert_eq!(result1.unwrap().len(), 41);
+        assert_eq!(part1(&data), None);
+        assert_eq!(part2(&data), None);
     }
 
     #[test]
     fn test_part2() {
-        let data = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
-        let result2 = part2(data);
+        let data = parse(&AdventParser::from_str(EXAMPLE)).unwrap();
+        let result2 = part2(&data);
 
-        assert_eq!(result2, 6);
+        assert_eq!(result2, Some(6));
     }
 
     #[test]
     fn test_part2_1() {
-        let data = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test2.txt"));
-        let result2 = part2(data);
+        // Only a stone at the left end of the guard's row traps them.
+        let data = parse(&AdventParser::from_str(".#..\n...#\n.^..\n..#.\n")).unwrap();
+        let result2 = part2(&data);
 
-        assert_eq!(result2, 1);
+        assert_eq!(result2, Some(1));
     }
 }
@@ -1,9 +1,5 @@
+use advent::parse::{ParseError, Parser as AdventParser};
 use clap::Parser;
-use itertools::Itertools;
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -22,7 +18,7 @@ fn main() {
         args.data_file
     };
 
-    let data = parse(&data_file);
+    let data = AdventParser::parse_or_exit(&data_file, parse);
 
     let result1 = part1(&data);
     println!("Part1: {}", result1);
@@ -85,29 +81,17 @@ fn part2(data: &Vec<Input>) -> u64 {
     solve(data, true)
 }
 
-fn parse(file: &str) -> Vec<Input> {
-    let file = File::open(file).expect("Failed to open file");
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .map(|line| line.expect("Failed to read line"))
-        .collect();
-
-    lines
-        .iter()
-        .map(|line| {
-            let (result, remainder) = line.split_once(':').unwrap();
-            let values: Vec<u64> = remainder
-                .trim()
+fn parse(parser: &AdventParser) -> Result<Vec<Input>, ParseError> {
+    parser.try_parse_lines(|line| {
+        let (result, remainder) = line.split_once(":")?;
+        Ok(Input {
+            result: line.parse(result)?,
+            values: remainder
                 .split_ascii_whitespace()
-                .map(|value| value.parse::<u64>().expect("Failed to parse value"))
-                .collect();
-            Input {
-                result: result.parse::<u64>().expect("Failed to parse result"),
-                values,
-            }
+                .map(|value| line.parse(value))
+                .collect::<Result<_, _>>()?,
         })
-        .collect_vec()
+    })
 }
 
 #[cfg(test)]
@@ -116,7 +100,10 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let data = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let data = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result1 = part1(&data);
 
         assert_eq!(result1, 3749);
@@ -124,7 +111,10 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        let data = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let data = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result2 = part2(&data);
 
         assert_eq!(result2, 11387);
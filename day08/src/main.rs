@@ -1,10 +1,7 @@
+use advent::parse::{ParseError, Parser as AdventParser};
 use clap::Parser;
 use itertools::Itertools;
-use std::{
-    collections::{HashMap, HashSet},
-    fs::File,
-    io::{BufRead, BufReader},
-};
+use std::collections::{HashMap, HashSet};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -35,7 +32,7 @@ fn main() {
         args.data_file
     };
 
-    let input = parse(&data_file);
+    let input = AdventParser::parse_or_exit(&data_file, parse);
 
     let result1 = part1(&input);
     println!("Part1: {}", result1);
@@ -125,45 +122,32 @@ fn part2(input: &Input) -> i64 {
         .len() as i64
 }
 
-fn parse(file: &str) -> Input {
-    let file = File::open(file).expect("Failed to open file");
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .map(|line| line.expect("Failed to read line"))
-        .collect();
-
-    let map_limits = Position {
-        x: lines[0].len() as i64,
-        y: lines.len() as i64,
-    };
-
-    Input {
-        antennas: lines
-            .into_iter()
-            .enumerate()
-            .flat_map(|(y, line)| {
-                line.chars()
-                    .enumerate()
-                    .filter(|(_, c)| *c != '.')
-                    .map(|(x, c)| {
-                        (
-                            c,
-                            Position {
-                                x: x as i64,
-                                y: y as i64,
-                            },
-                        )
-                    })
-                    .collect_vec()
+fn parse(parser: &AdventParser) -> Result<Input, ParseError> {
+    let map = parser.try_as_grid()?;
+
+    Ok(Input {
+        antennas: map
+            .iter()
+            .filter(|(_, c)| **c != '.')
+            .map(|(pos, c)| {
+                (
+                    *c,
+                    Position {
+                        x: pos.x as i64,
+                        y: pos.y as i64,
+                    },
+                )
             })
             .sorted_by(|(a, _), (b, _)| Ord::cmp(a, b))
             .chunk_by(|(c, _)| *c)
             .into_iter()
             .map(|(c, positions)| (c, positions.map(|(_, p)| p).collect_vec()))
             .collect(),
-        map_limits,
-    }
+        map_limits: Position {
+            x: map.width() as i64,
+            y: map.height() as i64,
+        },
+    })
 }
 
 #[cfg(test)]
@@ -172,7 +156,10 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let input = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result1 = part1(&input);
 
         assert_eq!(result1, 14);
@@ -180,7 +167,10 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let input = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result2 = part2(&input);
 
         assert_eq!(result2, 34);
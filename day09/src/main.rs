@@ -1,9 +1,6 @@
+use advent::parse::{ParseError, Parser as AdventParser};
 use clap::Parser;
 use itertools::Itertools;
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -27,7 +24,7 @@ fn main() {
         args.data_file
     };
 
-    let input = parse(&data_file);
+    let input = AdventParser::parse_or_exit(&data_file, parse);
 
     let result1 = part1(&input, args.debug);
     println!("Part1: {}", result1);
@@ -239,22 +236,24 @@ fn part2(input: &Input) -> i64 {
     checksum
 }
 
-fn parse(file: &str) -> Input {
-    let file = File::open(file).expect("Failed to open file");
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .map(|line| line.expect("Failed to read line"))
-        .collect();
-
-    Input {
-        blocks: lines
-            .get(0)
-            .unwrap()
-            .chars()
-            .map(|c| c.to_digit(10).unwrap() as i64)
-            .collect_vec(),
-    }
+fn parse(parser: &AdventParser) -> Result<Input, ParseError> {
+    let blocks = parser.try_parse_lines(|line| {
+        line.text()
+            .char_indices()
+            .map(|(i, c)| {
+                c.to_digit(10).map(|d| d as i64).ok_or_else(|| {
+                    line.error(&line.text()[i..i + c.len_utf8()], "expected a digit")
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+    })?;
+
+    Ok(Input {
+        blocks: blocks
+            .into_iter()
+            .next()
+            .ok_or_else(|| ParseError::new(parser.source(), "empty input"))?,
+    })
 }
 
 #[cfg(test)]
@@ -263,7 +262,10 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let input = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result1 = part1(&input, true);
 
         assert_eq!(result1, 1928);
@@ -271,7 +273,10 @@ mod tests {
 
     #[test]
     fn test_part1_2() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test2.txt"));
+        let input = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test2.txt"),
+        ))
+        .unwrap();
         let result1 = part1(&input, true);
 
         assert_eq!(result1, 5);
@@ -279,7 +284,10 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let input = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result2 = part2(&input);
 
         assert_eq!(result2, 2858);
@@ -1,10 +1,7 @@
+use advent::parse::{ParseError, Parser as AdventParser};
 use clap::Parser;
 use itertools::Itertools;
-use std::{
-    collections::{HashMap, HashSet, VecDeque},
-    fs::File,
-    io::{BufRead, BufReader},
-};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -35,7 +32,7 @@ fn main() {
         args.data_file
     };
 
-    let input = parse(&data_file);
+    let input = AdventParser::parse_or_exit(&data_file, parse);
 
     let result1 = part1(&input);
     println!("Part1: {}", result1);
@@ -240,42 +237,38 @@ fn part2(input: &Input) -> i64 {
         .sum::<i64>()
 }
 
-fn parse(file: &str) -> Input {
-    let file = File::open(file).expect("Failed to open file");
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .map(|line| line.expect("Failed to read line"))
-        .collect();
-
-    // Creates a HashMap<char, Vec<Position>>
-    let map_limits = Position {
-        x: lines[0].len() as i64,
-        y: lines.len() as i64,
-    };
-
-    Input {
-        elevations: lines
-            .into_iter()
-            .enumerate()
-            .flat_map(|(y, line)| {
-                line.chars()
-                    .enumerate()
-                    .filter(|(_, c)| *c != '.')
-                    .map(|(x, c)| {
-                        (
-                            Position {
-                                x: x as i64,
-                                y: y as i64,
-                            },
-                            c.to_digit(10).unwrap() as i64,
-                        )
-                    })
-                    .collect_vec()
+fn parse(parser: &AdventParser) -> Result<Input, ParseError> {
+    let rows = parser.try_parse_lines(|line| {
+        let elevations = line
+            .text()
+            .char_indices()
+            .filter(|(_, c)| *c != '.')
+            .map(|(x, c)| {
+                let elevation = c.to_digit(10).ok_or_else(|| {
+                    line.error(&line.text()[x..x + c.len_utf8()], "expected a digit or '.'")
+                })?;
+                Ok((
+                    Position {
+                        x: x as i64,
+                        y: line.number() as i64 - 1,
+                    },
+                    elevation as i64,
+                ))
             })
+            .collect::<Result<Vec<_>, ParseError>>()?;
+        Ok((line.text().len(), elevations))
+    })?;
+
+    Ok(Input {
+        map_limits: Position {
+            x: rows.first().map_or(0, |(width, _)| *width) as i64,
+            y: rows.len() as i64,
+        },
+        elevations: rows
+            .into_iter()
+            .flat_map(|(_, elevations)| elevations)
             .collect(),
-        map_limits,
-    }
+    })
 }
 
 #[cfg(test)]
@@ -284,7 +277,10 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let input = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result1 = part1(&input);
 
         assert_eq!(result1, 36);
@@ -292,7 +288,10 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let input = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result2 = part2(&input);
 
         assert_eq!(result2, 81);
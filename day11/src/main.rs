@@ -1,10 +1,6 @@
+use advent::parse::{ParseError, Parser as AdventParser};
 use clap::Parser;
-use itertools::Itertools;
-use std::{
-    collections::HashMap,
-    fs::File,
-    io::{BufRead, BufReader},
-};
+use std::collections::HashMap;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -28,7 +24,7 @@ fn main() {
         args.data_file
     };
 
-    let input = parse(&data_file);
+    let input = AdventParser::parse_or_exit(&data_file, parse);
 
     let result1 = part1(&input);
     println!("Part1: {}", result1);
@@ -117,22 +113,14 @@ fn part2(input: &Input) -> u128 {
         .sum::<i64>() as u128
 }
 
-fn parse(file: &str) -> Input {
-    let file = File::open(file).expect("Failed to open file");
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .map(|line| line.expect("Failed to read line"))
-        .collect();
-
-    Input {
-        values: lines
-            .get(0)
-            .unwrap()
-            .split_ascii_whitespace()
-            .map(|v| v.parse::<i64>().unwrap())
-            .collect_vec(),
-    }
+fn parse(parser: &AdventParser) -> Result<Input, ParseError> {
+    Ok(Input {
+        values: parser
+            .try_parse_lines(|line| line.whitespace_fields())?
+            .into_iter()
+            .next()
+            .ok_or_else(|| ParseError::new(parser.source(), "empty input"))?,
+    })
 }
 
 #[cfg(test)]
@@ -141,7 +129,10 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let input = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result1 = part1(&input);
 
         assert_eq!(result1, 55312);
@@ -149,7 +140,10 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let input = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result2 = part2(&input);
 
         assert_eq!(result2, 65601038650482);
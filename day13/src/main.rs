@@ -1,9 +1,7 @@
+use advent::parse::{Line, ParseError, Parser as AdventParser};
 use clap::Parser;
+use itertools::Itertools;
 use std::ops::{Add, Mul};
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-};
 use z3::ast::Ast;
 use z3::{ast::Int, SatResult};
 
@@ -42,7 +40,7 @@ fn main() {
         args.data_file
     };
 
-    let input = parse(&data_file);
+    let input = AdventParser::parse_or_exit(&data_file, parse);
 
     let result1 = part1(&input);
     println!("Part1: {}", result1);
@@ -146,73 +144,46 @@ fn part2(input: &Input) -> i64 {
         .sum::<i64>()
 }
 
-fn parse(file: &str) -> Input {
-    let file = File::open(file).expect("Failed to open file");
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .map(|line| line.expect("Failed to read line"))
-        .collect();
-
-    let mut games = Vec::new();
-
-    let mut i = 0;
-    while i < lines.len() {
-        //         Button A: X+94, Y+34
-        // Button B: X+22, Y+67
-        // Prize: X=8400, Y=5400
-        let button_a_line = &lines[i];
-        let button_b_line = &lines[i + 1];
-        let prize_line = &lines[i + 2];
-        games.push(Game {
-            button_a: Position {
-                x: button_a_line[(button_a_line.find("X").unwrap() + 1)..]
-                    .split_ascii_whitespace()
-                    .next()
-                    .unwrap()
-                    .trim_end_matches(',')
-                    .parse::<i64>()
-                    .unwrap(),
-                y: button_a_line[(button_a_line.find("Y").unwrap() + 1)..]
-                    .split_ascii_whitespace()
-                    .next()
-                    .unwrap()
-                    .parse::<i64>()
-                    .unwrap(),
-            },
-            button_b: Position {
-                x: button_b_line[(button_b_line.find("X").unwrap() + 1)..]
-                    .split_ascii_whitespace()
-                    .next()
-                    .unwrap()
-                    .trim_end_matches(',')
-                    .parse::<i64>()
-                    .unwrap(),
-                y: button_b_line[(button_b_line.find("Y").unwrap() + 1)..]
-                    .split_ascii_whitespace()
-                    .next()
-                    .unwrap()
-                    .parse::<i64>()
-                    .unwrap(),
-            },
-            prize: Position {
-                x: prize_line[(prize_line.find("X").unwrap() + 2)..]
-                    .split_ascii_whitespace()
-                    .next()
-                    .unwrap()
-                    .trim_end_matches(',')
-                    .parse::<i64>()
-                    .unwrap(),
-                y: prize_line[(prize_line.find("Y").unwrap() + 2)..]
-                    .parse::<i64>()
-                    .unwrap(),
-            },
-        });
-
-        i += 4;
+fn parse(parser: &AdventParser) -> Result<Input, ParseError> {
+    let games = parser
+        .try_sections()?
+        .map(|section| {
+            let lines = section.lines().collect_vec();
+            let [button_a, button_b, prize] = lines[..] else {
+                return Err(lines[0].error(lines[0].text(), "expected a game of 3 lines"));
+            };
+
+            Ok(Game {
+                button_a: parse_coordinates(&button_a, "Button A", "+")?,
+                button_b: parse_coordinates(&button_b, "Button B", "+")?,
+                prize: parse_coordinates(&prize, "Prize", "=")?,
+            })
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Input { games })
+}
+
+// Parses lines like `Button A: X+94, Y+34` or `Prize: X=8400, Y=5400`.
+fn parse_coordinates(line: &Line, label: &str, sign: &str) -> Result<Position, ParseError> {
+    let (name, coordinates) = line.split_once(": ")?;
+    if name != label {
+        return Err(line.error(name, format!("expected {:?}", label)));
     }
+    let (x, y) = coordinates
+        .split_once(", ")
+        .ok_or_else(|| line.error(coordinates, "missing \", \""))?;
+
+    Ok(Position {
+        x: line.parse(strip_prefix(line, x, &format!("X{}", sign))?)?,
+        y: line.parse(strip_prefix(line, y, &format!("Y{}", sign))?)?,
+    })
+}
 
-    Input { games }
+fn strip_prefix<'a>(line: &Line, field: &'a str, prefix: &str) -> Result<&'a str, ParseError> {
+    field
+        .strip_prefix(prefix)
+        .ok_or_else(|| line.error(field, format!("expected {:?}", prefix)))
 }
 
 #[cfg(test)]
@@ -221,7 +192,10 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let input = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result1 = part1(&input);
 
         assert_eq!(result1, 480);
@@ -229,7 +203,10 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let input = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result2 = part2(&input);
 
         assert_eq!(result2, 0);
@@ -1,9 +1,10 @@
+use advent::cycle;
+use advent::parse::{ParseError, Parser as AdventParser};
+use advent::pattern;
+use advent::position::{lcm, Position, Torus};
+use advent::render::Canvas;
 use clap::Parser;
 use itertools::Itertools;
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -16,15 +17,11 @@ struct Args {
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 struct Robot {
-    position: Position,
-    velocity: Position,
+    position: Position<i64>,
+    velocity: Position<i64>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct Position {
-    x: i64,
-    y: i64,
-}
+const BATHROOM: Torus = Torus::new(101, 103);
 
 #[derive(Debug, Clone, Hash)]
 struct Input {
@@ -39,155 +36,127 @@ fn main() {
         args.data_file
     };
 
-    let input = parse(&data_file);
+    let input = AdventParser::parse_or_exit(&data_file, parse);
 
-    let result1 = part1(&input, 101, 103);
+    let result1 = part1(&input, BATHROOM);
     println!("Part1: {}", result1);
 
-    println!("Part 2: {}", part2(&input))
+    if args.debug {
+        println!(
+            "Robots repeat every {} seconds",
+            picture_period(&input, BATHROOM)
+        );
+    }
+    match part2(&input, BATHROOM) {
+        Some(t) => {
+            if args.debug {
+                print!("{}", draw(&input, BATHROOM, t));
+            }
+            println!("Part 2: {}", t)
+        }
+        None => println!("Part 2: the robots never spread out"),
+    }
 }
 
-fn part1(input: &Input, width: usize, height: usize) -> i64 {
-    let final_positions = input
+fn part1(input: &Input, bathroom: Torus) -> i64 {
+    let mut quadrants = [0; 4];
+    input
         .robots
         .iter()
-        .map(|r| simulate_robot(r, width, height, 100))
-        .collect_vec();
-
-    let quadrant_1 = final_positions
-        .iter()
-        .filter(|p| p.x < ((width - 1) / 2) as i64 && p.y < ((height - 1) / 2) as i64)
-        .count();
-    let quadrant_2 = final_positions
-        .iter()
-        .filter(|p| p.x < ((width - 1) / 2) as i64 && p.y > ((height - 1) / 2) as i64)
-        .count();
-    let quadrant_3 = final_positions
-        .iter()
-        .filter(|p| p.x > ((width - 1) / 2) as i64 && p.y < ((height - 1) / 2) as i64)
-        .count();
-    let quadrant_4 = final_positions
-        .iter()
-        .filter(|p| p.x > ((width - 1) / 2) as i64 && p.y > ((height - 1) / 2) as i64)
-        .count();
+        .filter_map(|r| bathroom.quadrant(bathroom.advance(r.position, r.velocity, 100)))
+        .for_each(|q| quadrants[q] += 1);
 
-    (quadrant_1 * quadrant_2 * quadrant_3 * quadrant_4) as i64
+    quadrants.iter().product()
 }
 
-fn part2(input: &Input) -> i64 {
-    // A christmas tree with 500 pixels?
-    // Base should be 101 pixels wide?
-
-    let mut robots = input.robots.clone();
-    let mut t;
-
-    // Intersting timestamps:
-    // 48
-    // 104 -- 56
-    // 149 -- 45
-    // 207 -- 58
-    // 250 -- 43
-    // 310 -- 60
-    // 351 -- 41
-    // 413 -- 62
-    // 452 -- 39
-    // 516 -- 64
-    // 553 -- 37
-
-    let mut jump1 = 45;
-    let mut jump2 = 56;
-    let mut current_jump_number = 2;
-
-    robots
-        .iter_mut()
-        .for_each(|r| r.position = simulate_robot(r, 101, 103, 48));
-    t = 48;
-
-    loop {
-        let jump_amount;
-        if current_jump_number == 1 {
-            jump_amount = jump1;
-            jump1 -= 2;
-            current_jump_number = 2;
-        } else {
-            jump_amount = jump2;
-            jump2 += 2;
-            current_jump_number = 1;
-        }
-        t += jump_amount;
-        robots
-            .iter_mut()
-            .for_each(|r| r.position = simulate_robot(r, 101, 103, jump_amount));
-
-        println!("t = {}", t);
-        for y in 0..103 {
-            for x in 0..101 {
-                if robots.iter().any(|r| r.position == Position { x, y }) {
-                    print!("X");
-                } else {
-                    print!(".");
-                }
-            }
-            println!();
-        }
-    }
+// The robots' columns and rows move independently, so each axis repeats on its own and the
+// whole picture repeats once both do.
+fn picture_period(input: &Input, bathroom: Torus) -> i64 {
+    let axis_period = |axis: fn(&Position<i64>) -> i64, size: i64| {
+        let start = input.robots.iter().map(|r| axis(&r.position)).collect_vec();
+        let (_, period) = cycle::detect(start, |coordinates| {
+            coordinates
+                .iter()
+                .zip(&input.robots)
+                .map(|(c, r)| (c + axis(&r.velocity)).rem_euclid(size))
+                .collect_vec()
+        });
+        period as i64
+    };
+
+    lcm(
+        axis_period(|p| p.x, bathroom.width()),
+        axis_period(|p| p.y, bathroom.height()),
+    )
 }
 
-fn simulate_robot(r: &Robot, width: usize, height: usize, steps: i64) -> Position {
-    Position {
-        x: (r.position.x + r.velocity.x * steps).rem_euclid(width as i64),
-        y: (r.position.y + r.velocity.y * steps).rem_euclid(height as i64),
-    }
+// The first time at which no two robots share a cell, which is when they draw the tree. Every
+// picture after one full period has been seen before, so there's no need to look further.
+fn part2(input: &Input, bathroom: Torus) -> Option<i64> {
+    (0..picture_period(input, bathroom)).find(|t| {
+        input
+            .robots
+            .iter()
+            .map(|r| bathroom.advance(r.position, r.velocity, *t))
+            .all_unique()
+    })
 }
 
-fn parse(file: &str) -> Input {
-    let file = File::open(file).expect("Failed to open file");
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .map(|line| line.expect("Failed to read line"))
-        .collect();
+fn draw(input: &Input, bathroom: Torus, t: i64) -> Canvas {
+    let robots = input
+        .robots
+        .iter()
+        .filter_map(|r| Position::try_from(bathroom.advance(r.position, r.velocity, t)).ok())
+        .collect_vec();
 
-    Input {
-        robots: lines
-            .iter()
-            .map(|line| {
-                let (p, v) = line.split_once(' ').unwrap();
-                let (px, py) = p[2..].split_once(',').unwrap();
-                let (vx, vy) = v[2..].split_once(',').unwrap();
-
-                Robot {
-                    position: Position {
-                        x: px.parse::<i64>().unwrap(),
-                        y: py.parse::<i64>().unwrap(),
-                    },
-                    velocity: Position {
-                        x: vx.parse::<i64>().unwrap(),
-                        y: vy.parse::<i64>().unwrap(),
-                    },
-                }
+    Canvas::blank(bathroom.width() as usize, bathroom.height() as usize, '.').overlay(&robots, 'X')
+}
+
+fn parse(parser: &AdventParser) -> Result<Input, ParseError> {
+    Ok(Input {
+        robots: parser.try_parse_lines(|line| {
+            let (px, py, vx, vy) = pattern!("p={},{} v={},{}").parse(line)?;
+            Ok(Robot {
+                position: Position { x: px, y: py },
+                velocity: Position { x: vx, y: vy },
             })
-            .collect_vec(),
-    }
+        })?,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "\
+p=0,4 v=3,-3
+p=6,3 v=-1,-3
+p=10,3 v=-1,2
+p=2,0 v=2,-1
+p=0,0 v=1,3
+p=3,0 v=-2,-2
+p=7,6 v=-1,-3
+p=3,0 v=-1,-2
+p=9,3 v=2,3
+p=7,3 v=-1,2
+p=2,4 v=2,-3
+p=9,5 v=-3,-3
+";
+
     #[test]
     fn test_part1() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
-        let result1 = part1(&input, 11, 7);
+        let input = parse(&AdventParser::from_str(EXAMPLE)).unwrap();
+        let result1 = part1(&input, Torus::new(11, 7));
 
         assert_eq!(result1, 12);
     }
 
     #[test]
     fn test_part2() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
-        let result2 = part2(&input);
+        let input = parse(&AdventParser::from_str(EXAMPLE)).unwrap();
+        let result2 = part2(&input, Torus::new(11, 7));
 
-        assert_eq!(result2, 0);
+        assert_eq!(picture_period(&input, Torus::new(11, 7)), 77);
+        assert_eq!(result2, Some(1));
     }
 }
@@ -1,9 +1,8 @@
+use advent::parse::{ParseError, Parser as AdventParser};
 use clap::Parser;
 use itertools::Itertools;
 use std::{
     collections::{HashSet, VecDeque},
-    fs::File,
-    io::{BufRead, BufReader},
     ops::Add,
 };
 
@@ -49,7 +48,7 @@ fn main() {
         args.data_file
     };
 
-    let input = parse(&data_file);
+    let input = AdventParser::parse_or_exit(&data_file, parse);
 
     let result1 = part1(&input);
     println!("Part1: {}", result1);
@@ -227,80 +226,48 @@ fn move_robot2(input: &mut Input, instruction: Position) {
     input.robot = input.robot + instruction;
 }
 
-fn parse(file: &str) -> Input {
-    let file = File::open(file).expect("Failed to open file");
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .map(|line| line.expect("Failed to read line"))
-        .collect();
-
-    let grid_lines = lines.iter().take_while(|l| !l.is_empty()).collect_vec();
-    let boxes: HashSet<Position> = grid_lines
-        .iter()
-        .enumerate()
-        .flat_map(|(y, l)| {
-            l.chars()
-                .enumerate()
-                .filter(|(_, c)| *c == 'O')
-                .map(move |(x, _)| Position {
-                    x: x as i64,
-                    y: y as i64,
-                })
-        })
-        .collect();
-    let walls: HashSet<Position> = grid_lines
-        .iter()
-        .enumerate()
-        .flat_map(|(y, l)| {
-            l.chars()
-                .enumerate()
-                .filter(|(_, c)| *c == '#')
-                .map(move |(x, _)| Position {
-                    x: x as i64,
-                    y: y as i64,
-                })
-        })
-        .collect();
-    let robot = grid_lines
-        .iter()
-        .enumerate()
-        .flat_map(|(y, l)| {
-            l.chars()
-                .enumerate()
-                .filter(|(_, c)| *c == '@')
-                .map(move |(x, _)| Position {
-                    x: x as i64,
-                    y: y as i64,
-                })
-        })
-        .collect_vec()[0];
-
-    let instructions = lines
-        .iter()
-        .skip_while(|l| !l.is_empty())
-        .skip(1)
-        .flat_map(|line| {
-            line.chars().map(|c| {
-                if c == '<' {
-                    Position { x: -1, y: 0 }
-                } else if c == '^' {
-                    Position { x: 0, y: -1 }
-                } else if c == '>' {
-                    Position { x: 1, y: 0 }
-                } else {
-                    Position { x: 0, y: 1 }
-                }
+fn parse(parser: &AdventParser) -> Result<Input, ParseError> {
+    let (map, moves) = parser
+        .try_sections()?
+        .collect_tuple()
+        .ok_or_else(|| ParseError::new(parser.source(), "expected a map and a list of moves"))?;
+
+    let map = map.as_grid()?;
+    let cells_of = |c: char| {
+        map.iter()
+            .filter(move |(_, cell)| **cell == c)
+            .map(|(pos, _)| Position {
+                x: pos.x as i64,
+                y: pos.y as i64,
             })
-        })
+    };
+    let robot = cells_of('@')
+        .exactly_one()
+        .map_err(|_| ParseError::new(parser.source(), "expected exactly one robot '@'"))?;
+
+    let instructions = moves
+        .parse_lines(|line| {
+            line.text()
+                .char_indices()
+                .map(|(i, c)| match c {
+                    '<' => Ok(Position { x: -1, y: 0 }),
+                    '^' => Ok(Position { x: 0, y: -1 }),
+                    '>' => Ok(Position { x: 1, y: 0 }),
+                    'v' => Ok(Position { x: 0, y: 1 }),
+                    _ => Err(line.error(&line.text()[i..i + c.len_utf8()], "expected a move")),
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })?
+        .into_iter()
+        .flatten()
         .collect_vec();
 
-    Input {
-        boxes,
-        walls,
+    Ok(Input {
+        boxes: cells_of('O').collect(),
+        walls: cells_of('#').collect(),
         robot,
         instructions,
-    }
+    })
 }
 
 #[cfg(test)]
@@ -309,7 +276,10 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let input = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result1 = part1(&input);
 
         assert_eq!(result1, 10092);
@@ -317,7 +287,10 @@ mod tests {
 
     #[test]
     fn test_part1_2() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test2.txt"));
+        let input = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test2.txt"),
+        ))
+        .unwrap();
         let result1 = part1(&input);
 
         assert_eq!(result1, 2028);
@@ -325,7 +298,10 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let input = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result2 = part2(&input);
 
         assert_eq!(result2, 9021);
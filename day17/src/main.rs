@@ -1,11 +1,8 @@
+use advent::parse::{ParseError, Parser as AdventParser};
 use clap::Parser;
 use itertools::Itertools;
 use log::trace;
-use std::{
-    collections::HashSet,
-    fs::File,
-    io::{BufRead, BufReader},
-};
+use std::collections::HashSet;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -39,7 +36,7 @@ fn main() {
         args.data_file
     };
 
-    let input = parse(&data_file);
+    let input = AdventParser::parse_or_exit(&data_file, parse);
 
     let result1 = part1(&input);
     println!("Part1: {}", result1);
@@ -385,38 +382,30 @@ fn get_combo_operand_value(operand: u64, a: u64, b: u64, c: u64) -> u64 {
     }
 }
 
-fn parse(file: &str) -> Input {
-    let file = File::open(file).expect("Failed to open file");
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .map(|line| line.expect("Failed to read line"))
-        .collect();
-
-    let (a, b, c) = lines
-        .iter()
-        .take_while(|l| !l.is_empty())
-        .map(|l| l.split_once(": ").unwrap().1.parse::<u64>().unwrap())
+fn parse(parser: &AdventParser) -> Result<Input, ParseError> {
+    let (registers, program) = parser
+        .try_sections()?
         .collect_tuple()
-        .unwrap();
+        .ok_or_else(|| ParseError::new(parser.source(), "expected registers and a program"))?;
+
+    let (a, b, c) = registers
+        .parse_lines(|line| line.parse::<u64>(line.split_once(": ")?.1))?
+        .into_iter()
+        .collect_tuple()
+        .ok_or_else(|| ParseError::new(parser.source(), "expected 3 registers"))?;
+    let program = program.single_line()?;
 
-    Input {
+    Ok(Input {
         register_a: a,
         register_b: b,
         register_c: c,
-        program: lines
-            .iter()
-            .skip_while(|line| !line.is_empty())
-            .filter(|line| !line.is_empty())
-            .next()
-            .unwrap()
-            .split_once(": ")
-            .unwrap()
+        program: program
+            .split_once(": ")?
             .1
             .split(',')
-            .map(|page| page.parse::<u64>().unwrap())
-            .collect_vec(),
-    }
+            .map(|value| program.parse(value))
+            .collect::<Result<_, _>>()?,
+    })
 }
 
 #[cfg(test)]
@@ -425,7 +414,10 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let input = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result1 = part1(&input);
 
         assert_eq!(result1, "4,6,3,5,6,3,5,2,1,0");
@@ -444,7 +436,10 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let input = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result2 = part2(&input);
 
         assert_eq!(result2, 117440);
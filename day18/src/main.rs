@@ -1,11 +1,10 @@
+use advent::parse::{ParseError, Parser as AdventParser};
+use advent::position::{Position_isize, Rect};
+use advent::search::dijkstra;
+use advent::union_find::first_blocking_obstacle;
 use clap::Parser;
 use itertools::Itertools;
-use std::{
-    cmp::Reverse,
-    collections::{BinaryHeap, HashSet},
-    fs::File,
-    io::{BufRead, BufReader},
-};
+use std::collections::HashSet;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -16,15 +15,9 @@ struct Args {
     debug: bool,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct Position {
-    x: i64,
-    y: i64,
-}
-
 #[derive(Debug, Clone, Hash)]
 struct Input {
-    bytes: Vec<Position>,
+    bytes: Vec<Position_isize>,
 }
 
 fn main() {
@@ -35,135 +28,109 @@ fn main() {
         args.data_file
     };
 
-    let input = parse(&data_file);
+    let input = AdventParser::parse_or_exit(&data_file, parse);
 
-    let result1 = part1(&input, Position { x: 70, y: 70 }, 1024);
+    let exit = Position_isize { x: 70, y: 70 };
+    let result1 = part1(&input, exit, 1024);
     println!("Part1: {}", result1);
 
-    println!("Part 2: {}", part2(&input, Position { x: 70, y: 70 }))
+    match part2(&input, exit) {
+        Some(byte) => println!("Part 2: {}", byte),
+        None => println!("Part 2: the exit is never cut off"),
+    }
 }
 
-fn part1(input: &Input, bounds: Position, bytes_to_drop: i64) -> i64 {
-    let mut to_process = BinaryHeap::new();
-    to_process.push(Reverse((0, Position { x: 0, y: 0 })));
+// The memory space runs from the origin to `exit`, inclusive.
+fn part1(input: &Input, exit: Position_isize, bytes_to_drop: usize) -> i64 {
+    let bounds = Rect::from_inclusive(Position_isize { x: 0, y: 0 }, exit);
     let bytes = input
         .bytes
         .iter()
-        .cloned()
-        .take(bytes_to_drop as usize)
-        .collect_vec();
-
-    let mut visited = HashSet::new();
-    //visited.insert(Position { x: 0, y: 0 });
-
-    while !to_process.is_empty() {
-        let Reverse((dist, pos)) = to_process.pop().expect("Failed to pop");
-
-        if !visited.insert(pos) {
-            continue;
-        }
-
-        if pos == bounds {
-            return dist;
-        }
-
-        let left = Position {
-            x: pos.x - 1,
-            y: pos.y,
-        };
-        if pos.x > 0 && !bytes.contains(&left) {
-            to_process.push(Reverse((dist + 1, left)));
-        }
-
-        let right = Position {
-            x: pos.x + 1,
-            y: pos.y,
-        };
-        if pos.x < bounds.x && !bytes.contains(&right) {
-            to_process.push(Reverse((dist + 1, right)));
-        }
-
-        let up = Position {
-            x: pos.x,
-            y: pos.y - 1,
-        };
-        if pos.y > 0 && !bytes.contains(&up) {
-            to_process.push(Reverse((dist + 1, up)));
-        }
-
-        let down = Position {
-            x: pos.x,
-            y: pos.y + 1,
-        };
-        if pos.y < bounds.y && !bytes.contains(&down) {
-            to_process.push(Reverse((dist + 1, down)));
-        }
-    }
-
-    0
+        .take(bytes_to_drop)
+        .collect::<HashSet<_>>();
+
+    dijkstra(
+        Position_isize { x: 0, y: 0 },
+        |pos| {
+            pos.neighbors4_in(bounds)
+                .filter(|p| !bytes.contains(p))
+                .map(|p| (p, 1))
+                .collect_vec()
+        },
+        |pos| *pos == exit,
+    )
+    .cost()
+    .unwrap_or(0)
 }
 
-fn part2(input: &Input, bounds: Position) -> String {
-    let mut low = 0;
-    let mut high = input.bytes.len();
-    let mut guess = high / 2;
-    loop {
-        println!("Guess: {}", guess);
-        if part1(&input, bounds, guess as i64) == 0 {
-            high = guess;
-        } else {
-            low = guess;
-        }
-
-        if low == high - 1 {
-            break;
-        }
-
-        guess = (low + high) / 2;
-    }
+fn part2(input: &Input, exit: Position_isize) -> Option<String> {
+    let start = Position_isize { x: 0, y: 0 };
+    let first =
+        first_blocking_obstacle(Rect::from_inclusive(start, exit), &input.bytes, start, exit)?;
 
-    format!("{},{}", input.bytes[guess].x, input.bytes[guess].y)
+    Some(format!("{},{}", input.bytes[first].x, input.bytes[first].y))
 }
 
-fn parse(file: &str) -> Input {
-    let file = File::open(file).expect("Failed to open file");
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .map(|line| line.expect("Failed to read line"))
-        .collect();
-
-    Input {
-        bytes: lines
-            .iter()
-            .map(|line| {
-                let (x, y) = line.split_once(',').unwrap();
-                Position {
-                    x: x.parse::<i64>().unwrap(),
-                    y: y.parse::<i64>().unwrap(),
-                }
+fn parse(parser: &AdventParser) -> Result<Input, ParseError> {
+    Ok(Input {
+        bytes: parser.try_parse_lines(|line| {
+            let (x, y) = line.split_once(",")?;
+            Ok(Position_isize {
+                x: line.parse(x)?,
+                y: line.parse(y)?,
             })
-            .collect_vec(),
-    }
+        })?,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "\
+5,4
+4,2
+4,5
+3,0
+2,1
+6,3
+2,4
+1,5
+0,6
+3,3
+2,6
+5,1
+1,2
+5,5
+2,5
+6,5
+1,4
+0,4
+6,4
+1,1
+6,1
+1,0
+0,5
+1,6
+2,0
+";
+
     #[test]
     fn test_part1() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
-        let result1 = part1(&input, Position { x: 6, y: 6 }, 12);
+        let input = parse(&AdventParser::from_str(EXAMPLE)).unwrap();
+        let result1 = part1(&input, Position_isize { x: 6, y: 6 }, 12);
 
         assert_eq!(result1, 22);
     }
 
     #[test]
     fn test_part2() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
-        let result2 = part2(&input, Position { x: 6, y: 6 });
+        let input = parse(&AdventParser::from_str(EXAMPLE)).unwrap();
+        let result2 = part2(&input, Position_isize { x: 6, y: 6 });
+
+        assert_eq!(result2, Some("6,1".to_string()));
 
-        assert_eq!(result2, "6,1");
+        let input = parse(&AdventParser::from_str("1,1\n2,2\n")).unwrap();
+        assert_eq!(part2(&input, Position_isize { x: 6, y: 6 }), None);
     }
 }
@@ -1,11 +1,8 @@
+use advent::parse::{ParseError, Parser as AdventParser};
 use cached::proc_macro::cached;
 use clap::Parser;
 use itertools::Itertools;
-use std::{
-    collections::{BinaryHeap, HashSet},
-    fs::File,
-    io::{BufRead, BufReader},
-};
+use std::collections::{BinaryHeap, HashSet};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -30,7 +27,7 @@ fn main() {
         args.data_file
     };
 
-    let input = parse(&data_file);
+    let input = AdventParser::parse_or_exit(&data_file, parse);
 
     let result1 = part1(&input);
     println!("Part1: {}", result1);
@@ -121,28 +118,25 @@ fn possibilities(
         .sum()
 }
 
-fn parse(file: &str) -> Input {
-    let file = File::open(file).expect("Failed to open file");
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .map(|line| line.expect("Failed to read line"))
-        .collect();
+fn parse(parser: &AdventParser) -> Result<Input, ParseError> {
+    let (towels, targets) = parser
+        .try_sections()?
+        .collect_tuple()
+        .ok_or_else(|| ParseError::new(parser.source(), "expected towels and targets"))?;
 
-    Input {
-        source_towels: lines
-            .get(0)
-            .unwrap()
+    Ok(Input {
+        source_towels: towels
+            .single_line()?
+            .text()
             .split_ascii_whitespace()
             .map(|t| t.trim_end_matches(',').chars().collect_vec())
             .sorted()
             .collect_vec(),
-        target_towels: lines
-            .iter()
-            .skip(2)
-            .map(|t| t.chars().collect_vec())
+        target_towels: targets
+            .lines()
+            .map(|t| t.text().chars().collect_vec())
             .collect_vec(),
-    }
+    })
 }
 
 #[cfg(test)]
@@ -151,7 +145,10 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let input = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result1 = part1(&input);
 
         assert_eq!(result1, 6);
@@ -159,7 +156,10 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let input = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result2 = part2(&input);
 
         assert_eq!(result2, 16);
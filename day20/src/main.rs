@@ -1,11 +1,8 @@
-use advent::parse::Parser as AventParser;
+use advent::grid::Grid;
+use advent::maze::Maze;
+use advent::parse::{ParseError, Parser as AdventParser};
 use advent::position::Position;
 use clap::Parser;
-use itertools::Itertools;
-use std::{
-    cmp::Reverse,
-    collections::{BinaryHeap, HashMap, HashSet},
-};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -18,7 +15,7 @@ struct Args {
 
 #[derive(Debug, Clone)]
 struct Input {
-    walls: HashSet<Position<isize>>,
+    maze: Maze,
     start: Position<isize>,
     exit: Position<isize>,
 }
@@ -31,376 +28,124 @@ fn main() {
         args.data_file
     };
 
-    let input = parse(&data_file);
+    let input = AdventParser::parse_or_exit(&data_file, parse);
 
-    let result1 = part1(&input);
-    println!("Part1: {}", result1);
-
-    println!("Part 2: {}", part2(&input))
-}
-
-fn part1(input: &Input) -> usize {
-    let max_bounds = Position {
-        x: input.walls.iter().map(|w| w.x).max().unwrap(),
-        y: input.walls.iter().map(|w| w.y).max().unwrap(),
-    };
-    // First find the best route, then we can start cheating
-    // Map from a Position to the number of seconds it takes to get to the end. This is without cheating.
-    let mut shortest_path_to_end = HashMap::new();
-
-    let mut states = BinaryHeap::new();
-    states.push(Reverse((0, input.exit)));
-
-    while states.len() > 0 {
-        let Reverse((time, pos)) = states.pop().unwrap();
-
-        if shortest_path_to_end.contains_key(&pos) {
-            continue;
-        }
-        shortest_path_to_end.insert(pos.clone(), time);
-
-        if let Some(new_pos) = pos.checked_sub_x(1) {
-            if !input.walls.contains(&new_pos) {
-                states.push(Reverse((time + 1, new_pos)));
-            }
+    match (part1(&input), part2(&input)) {
+        (Some(result1), Some(result2)) => {
+            println!("Part1: {}", result1);
+            println!("Part 2: {}", result2)
         }
-
-        if let Some(new_pos) = pos.limited_add_x(1, max_bounds) {
-            if !input.walls.contains(&new_pos) {
-                states.push(Reverse((time + 1, new_pos)));
-            }
-        }
-
-        if let Some(new_pos) = pos.checked_sub_y(1) {
-            if !input.walls.contains(&new_pos) {
-                states.push(Reverse((time + 1, new_pos)));
-            }
-        }
-
-        if let Some(new_pos) = pos.limited_add_y(1, max_bounds) {
-            if !input.walls.contains(&new_pos) {
-                states.push(Reverse((time + 1, new_pos)));
-            }
+        _ => {
+            eprintln!("{}: the exit can't be reached from the start", data_file);
+            std::process::exit(1);
         }
     }
-
-    let shortest_no_cheat_path = shortest_path_to_end.get(&input.start).unwrap();
-    println!("Shortest path: {:?}", shortest_no_cheat_path);
-
-    let mut shortest_path_to_start = HashMap::new();
-
-    let mut states = BinaryHeap::new();
-    states.push(Reverse((0, input.start)));
-
-    while states.len() > 0 {
-        let Reverse((time, pos)) = states.pop().unwrap();
-
-        if shortest_path_to_start.contains_key(&pos) {
-            continue;
-        }
-        shortest_path_to_start.insert(pos.clone(), time);
-
-        if let Some(new_pos) = pos.checked_sub_x(1) {
-            if !input.walls.contains(&new_pos) {
-                states.push(Reverse((time + 1, new_pos)));
-            }
-        }
-
-        if let Some(new_pos) = pos.limited_add_x(1, max_bounds) {
-            if !input.walls.contains(&new_pos) {
-                states.push(Reverse((time + 1, new_pos)));
-            }
-        }
-
-        if let Some(new_pos) = pos.checked_sub_y(1) {
-            if !input.walls.contains(&new_pos) {
-                states.push(Reverse((time + 1, new_pos)));
-            }
-        }
-
-        if let Some(new_pos) = pos.limited_add_y(1, max_bounds) {
-            if !input.walls.contains(&new_pos) {
-                states.push(Reverse((time + 1, new_pos)));
-            }
-        }
-    }
-
-    let mut cheats = HashMap::new();
-    for y in 0..=max_bounds.y {
-        for x in 0..=max_bounds.x {
-            let pos = Position { x, y };
-
-            if input.walls.contains(&pos) {
-                continue;
-            }
-
-            // Can cheat for two steps, but really that's just into a wall and immediately out.
-            // The new time will be the steps from start + 2 (time in wall) + steps to end of the new place
-
-            let start_time = *shortest_path_to_start.get(&pos).unwrap();
-
-            for dir in vec![
-                Position { x: 0, y: 1 },
-                Position { x: 0, y: -1 },
-                Position { x: -1, y: 0 },
-                Position { x: 1, y: 0 },
-            ]
-            .into_iter()
-            {
-                let wall_candidate = pos + dir;
-                if wall_candidate.x >= 0
-                    && wall_candidate.y >= 0
-                    && wall_candidate.x <= max_bounds.x
-                    && wall_candidate.y <= max_bounds.y
-                    && input.walls.contains(&wall_candidate)
-                {
-                    vec![
-                        Position { x: 0, y: 1 },
-                        Position { x: 0, y: -1 },
-                        Position { x: -1, y: 0 },
-                        Position { x: 1, y: 0 },
-                    ]
-                    .into_iter()
-                    .filter_map(|p| {
-                        let dest = wall_candidate + p;
-                        if dest.x < 0
-                            || dest.y < 0
-                            || dest.x >= max_bounds.x
-                            || dest.y >= max_bounds.y
-                        {
-                            return None;
-                        }
-                        if input.walls.contains(&dest) {
-                            return None;
-                        }
-                        if dest == pos {
-                            return None;
-                        }
-
-                        Some(dest)
-                    })
-                    .for_each(|dest| {
-                        cheats.insert(
-                            (pos.clone(), dest.clone()),
-                            start_time + 2 + shortest_path_to_end.get(&dest).unwrap(),
-                        );
-                    });
-                }
-            }
-        }
-    }
-
-    println!(
-        "Cheats: {:?}",
-        cheats
-            .iter()
-            .filter(|(_, t)| *t < shortest_no_cheat_path)
-            .sorted_by_key(|(_, t)| *t)
-            .count()
-    );
-
-    cheats
-        .iter()
-        .filter(|(_, t)| **t <= shortest_no_cheat_path - 100)
-        .count()
 }
 
-fn part2(input: &Input) -> usize {
-    let max_bounds = Position {
-        x: input.walls.iter().map(|w| w.x).max().unwrap(),
-        y: input.walls.iter().map(|w| w.y).max().unwrap(),
-    };
-    // First find the best route, then we can start cheating
-    // Map from a Position to the number of seconds it takes to get to the end. This is without cheating.
-    let mut shortest_path_to_end = HashMap::new();
-
-    let mut states = BinaryHeap::new();
-    states.push(Reverse((0, input.exit)));
-
-    while states.len() > 0 {
-        let Reverse((time, pos)) = states.pop().unwrap();
-
-        if shortest_path_to_end.contains_key(&pos) {
-            continue;
-        }
-        shortest_path_to_end.insert(pos.clone(), time);
-
-        let new_pos = pos - Position { x: 1, y: 0 };
-        if pos.x > 0 && !input.walls.contains(&new_pos) {
-            states.push(Reverse((time + 1, new_pos)));
-        }
-
-        let new_pos = pos + Position { x: 1, y: 0 };
-        if pos.x < max_bounds.x && !input.walls.contains(&new_pos) {
-            states.push(Reverse((time + 1, new_pos)));
-        }
-
-        let new_pos = pos - Position { x: 0, y: 1 };
-        if pos.y > 0 && !input.walls.contains(&new_pos) {
-            states.push(Reverse((time + 1, new_pos)));
-        }
-
-        let new_pos = pos + Position { x: 0, y: 1 };
-        if pos.y < max_bounds.y && !input.walls.contains(&new_pos) {
-            states.push(Reverse((time + 1, new_pos)));
-        }
-    }
-
-    let shortest_no_cheat_path = shortest_path_to_end.get(&input.start).unwrap();
-    println!("Shortest path: {:?}", shortest_no_cheat_path);
-
-    let mut shortest_path_to_start = HashMap::new();
+// Cheat times are compared by subtraction, so keep the distances signed.
+fn distances_from(input: &Input, from: Position<isize>) -> Grid<Option<isize>> {
+    input.maze.distance_field_with([from], |_, _| 1)
+}
 
-    let mut states = BinaryHeap::new();
-    states.push(Reverse((0, input.start)));
+fn part1(input: &Input) -> Option<usize> {
+    count_cheats(input, 2, 100)
+}
 
-    while states.len() > 0 {
-        let Reverse((time, pos)) = states.pop().unwrap();
+fn part2(input: &Input) -> Option<usize> {
+    count_cheats(input, 20, 100)
+}
 
-        if shortest_path_to_start.contains_key(&pos) {
+// Counts the cheats of up to `max_cheat` picoseconds that save at least `min_saving`
+// picoseconds, or None when the exit can't be reached at all.
+fn count_cheats(input: &Input, max_cheat: isize, min_saving: isize) -> Option<usize> {
+    let shortest_path_to_end = distances_from(input, input.exit);
+    let shortest_path_to_start = distances_from(input, input.start);
+    let shortest_no_cheat_path = shortest_path_to_end[input.start]?;
+
+    let mut cheats = 0;
+    for pos in input.maze.bounds().iter() {
+        // Walls and cells cut off from the start are never on a route.
+        let Some(start_time) = shortest_path_to_start[pos] else {
             continue;
-        }
-        shortest_path_to_start.insert(pos.clone(), time);
-
-        let new_pos = pos - Position { x: 1, y: 0 };
-        if pos.x > 0 && !input.walls.contains(&new_pos) {
-            states.push(Reverse((time + 1, new_pos)));
-        }
-
-        let new_pos = pos + Position { x: 1, y: 0 };
-        if pos.x < max_bounds.x && !input.walls.contains(&new_pos) {
-            states.push(Reverse((time + 1, new_pos)));
-        }
-
-        let new_pos = pos - Position { x: 0, y: 1 };
-        if pos.y > 0 && !input.walls.contains(&new_pos) {
-            states.push(Reverse((time + 1, new_pos)));
-        }
-
-        let new_pos = pos + Position { x: 0, y: 1 };
-        if pos.y < max_bounds.y && !input.walls.contains(&new_pos) {
-            states.push(Reverse((time + 1, new_pos)));
-        }
-    }
-
-    let mut cheats = HashMap::new();
-    for y in 0..=max_bounds.y {
-        for x in 0..=max_bounds.x {
-            let pos = Position { x, y };
-
-            if input.walls.contains(&pos) {
-                continue;
-            }
-
-            // Can cheat for two steps, but really that's just into a wall and immediately out.
-            // The new time will be the steps from start + 2 (time in wall) + steps to end of the new place
-
-            let start_time = *shortest_path_to_start.get(&pos).unwrap();
-
-            for addx in 0..=20 {
-                for addy in 0..=20 {
-                    if addy + addx > 20 {
-                        continue;
-                    }
-
-                    let dest = pos + Position { x: addx, y: addy };
-                    if !(dest.x < 0
-                        || dest.y < 0
-                        || dest.x > max_bounds.x
-                        || dest.y > max_bounds.y
-                        || input.walls.contains(&dest))
-                    {
-                        cheats.insert(
-                            (pos, dest),
-                            start_time + addx + addy + shortest_path_to_end.get(&dest).unwrap(),
-                        );
-                    }
-
-                    let dest = pos + Position { x: -addx, y: addy };
-                    if !(dest.x < 0
-                        || dest.y < 0
-                        || dest.x > max_bounds.x
-                        || dest.y > max_bounds.y
-                        || input.walls.contains(&dest))
-                    {
-                        cheats.insert(
-                            (pos, dest),
-                            start_time + addx + addy + shortest_path_to_end.get(&dest).unwrap(),
-                        );
-                    }
-
-                    let dest = pos + Position { x: addx, y: -addy };
-                    if !(dest.x < 0
-                        || dest.y < 0
-                        || dest.x > max_bounds.x
-                        || dest.y > max_bounds.y
-                        || input.walls.contains(&dest))
-                    {
-                        cheats.insert(
-                            (pos, dest),
-                            start_time + addx + addy + shortest_path_to_end.get(&dest).unwrap(),
-                        );
-                    }
-
-                    let dest = pos + Position { x: -addx, y: -addy };
-                    if !(dest.x < 0
-                        || dest.y < 0
-                        || dest.x > max_bounds.x
-                        || dest.y > max_bounds.y
-                        || input.walls.contains(&dest))
-                    {
-                        cheats.insert(
-                            (pos, dest),
-                            start_time + addx + addy + shortest_path_to_end.get(&dest).unwrap(),
-                        );
-                    }
-                }
-            }
-        }
+        };
+
+        // A cheat goes straight through walls, so it takes as long as the Manhattan distance.
+        // The new time is the steps from start + time cheating + steps to the end from there.
+        cheats += pos
+            .within_manhattan(max_cheat)
+            .filter_map(|dest| {
+                let end_time = shortest_path_to_end.get(dest).copied().flatten()?;
+                Some(start_time + pos.manhattan(dest) + end_time)
+            })
+            .filter(|t| *t <= shortest_no_cheat_path - min_saving)
+            .count();
     }
 
-    println!(
-        "Cheats: {:?}",
-        cheats
-            .iter()
-            .filter(|(_, t)| *t < shortest_no_cheat_path)
-            .sorted_by_key(|(_, t)| *t)
-            .count()
-    );
-
-    cheats
-        .iter()
-        .filter(|(_, t)| **t <= shortest_no_cheat_path - 100)
-        .count()
+    Some(cheats)
 }
 
-fn parse(file: &str) -> Input {
-    let maze = AventParser::new(file).as_maze();
+fn parse(parser: &AdventParser) -> Result<Input, ParseError> {
+    let maze = parser.try_as_maze()?;
 
-    Input {
-        start: maze.get_only_position('S').unwrap(),
-        exit: maze.get_only_position('E').unwrap(),
-        walls: maze.walls,
-    }
+    Ok(Input {
+        start: maze.get_only_position('S')?,
+        exit: maze.get_only_position('E')?,
+        maze,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "\
+###############
+#...#...#.....#
+#.#.#.#.#.###.#
+#S#...#.#.#...#
+#######.#.#.###
+#######.#.#...#
+#######.#.###.#
+###..E#...#...#
+###.#######.###
+#...###...#...#
+#.#####.#.###.#
+#.#...#.#.#...#
+#.#.#.#.#.#.###
+#...#...#...###
+###############
+";
+
     #[test]
     fn test_part1() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
-        let result1 = part1(&input);
+        let input = parse(&AdventParser::from_str(EXAMPLE)).unwrap();
 
-        assert_eq!(result1, 0);
+        assert_eq!(part1(&input), Some(0));
+        assert_eq!(count_cheats(&input, 2, 64), Some(1));
+        assert_eq!(count_cheats(&input, 2, 20), Some(5));
+        assert_eq!(count_cheats(&input, 2, 2), Some(44));
     }
 
     #[test]
     fn test_part2() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
-        let result2 = part2(&input);
+        let input = parse(&AdventParser::from_str(EXAMPLE)).unwrap();
+
+        assert_eq!(part2(&input), Some(0));
+        assert_eq!(count_cheats(&input, 20, 76), Some(3));
+        assert_eq!(count_cheats(&input, 20, 50), Some(285));
+    }
+
+    #[test]
+    fn test_no_path() {
+        let input = parse(&AdventParser::from_str("#####\n#S#E#\n#####\n")).unwrap();
+
+        assert_eq!(part1(&input), None);
 
-        assert_eq!(result2, 0);
+        // The floor cell at the top right is cut off from both ends.
+        let input = parse(&AdventParser::from_str(
+            "#######\n#S..#.#\n#..E###\n#######\n",
+        ))
+        .unwrap();
+        assert_eq!(count_cheats(&input, 20, 1), Some(0));
     }
 }
@@ -1,10 +1,9 @@
+use advent::parse::{ParseError, Parser as AdventParser};
 use clap::Parser;
 use std::{
     cmp::Reverse,
     collections::{BinaryHeap, HashMap},
-    fs::File,
     hash::Hash,
-    io::{BufRead, BufReader},
     ops::Add,
     vec,
 };
@@ -48,7 +47,7 @@ fn main() {
         args.data_file
     };
 
-    let input = parse(&data_file);
+    let input = AdventParser::parse_or_exit(&data_file, parse);
 
     let result1 = part1(&input);
     println!("Part1: {}", result1);
@@ -445,15 +444,17 @@ fn part2(input: &Input) -> i64 {
         .sum()
 }
 
-fn parse(file: &str) -> Input {
-    let file = File::open(file).expect("Failed to open file");
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .map(|line| line.expect("Failed to read line"))
-        .collect();
-
-    Input { codes: lines }
+fn parse(parser: &AdventParser) -> Result<Input, ParseError> {
+    Ok(Input {
+        codes: parser.try_parse_lines(|line| {
+            let digits = line
+                .text()
+                .strip_suffix('A')
+                .ok_or_else(|| line.error(line.text(), "expected a code ending in 'A'"))?;
+            line.parse::<u64>(digits)?;
+            Ok(line.text().to_string())
+        })?,
+    })
 }
 
 #[cfg(test)]
@@ -462,7 +463,10 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let input = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result1 = part1(&input);
 
         assert_eq!(result1, 126384);
@@ -470,7 +474,10 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let input = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result2 = part2(&input);
 
         assert_eq!(result2, 0);
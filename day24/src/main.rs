@@ -1,9 +1,8 @@
+use advent::parse::{ParseError, Parser as AdventParser};
 use clap::Parser;
 use itertools::Itertools;
 use std::{
     collections::{HashMap, HashSet},
-    fs::File,
-    io::{BufRead, BufReader},
     rc::Rc,
 };
 
@@ -98,7 +97,7 @@ fn main() {
         args.data_file
     };
 
-    let input = parse(&data_file);
+    let input = AdventParser::parse_or_exit(&data_file, parse);
 
     let result1 = part1(&input);
     println!("Part1: {}", result1);
@@ -694,44 +693,44 @@ fn _part2(input: &Input) -> String {
     results.iter().sorted().join(",")
 }
 
-fn parse(file: &str) -> Input {
-    let file = File::open(file).expect("Failed to open file");
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .map(|line| line.expect("Failed to read line"))
-        .collect();
-
-    let values = lines
-        .iter()
-        .take_while(|l| !l.is_empty())
-        .map(|l| {
-            let (gate, value) = l.split_once(": ").unwrap();
-            (gate.to_string(), value.parse::<i64>().unwrap())
-        })
+fn parse(parser: &AdventParser) -> Result<Input, ParseError> {
+    let (values, gates) = parser
+        .try_sections()?
+        .collect_tuple()
+        .ok_or_else(|| ParseError::new(parser.source(), "expected wire values and gates"))?;
+
+    let values = values
+        .parse_lines(|line| {
+            let (wire, value) = line.split_once(": ")?;
+            Ok((wire.to_string(), line.parse::<i64>(value)?))
+        })?
+        .into_iter()
         .collect::<HashMap<String, i64>>();
 
-    let operations = lines
-        .iter()
-        .skip_while(|l| !l.is_empty())
-        .filter(|l| !l.is_empty())
-        .map(|l| {
-            let (left, gate, right, _arrow, target) = l.split_whitespace().collect_tuple().unwrap();
-            Operation {
-                left: left.to_string(),
-                right: right.to_string(),
-                target: target.to_string(),
-                gate: match gate {
-                    "OR" => Gate::OR,
-                    "XOR" => Gate::XOR,
-                    "AND" => Gate::AND,
-                    _ => panic!("Unknown gate: {}", gate),
-                },
-            }
+    let operations = gates.parse_lines(|line| {
+        let (left, gate, right, arrow, target) = line
+            .text()
+            .split_whitespace()
+            .collect_tuple()
+            .ok_or_else(|| line.error(line.text(), "expected `<wire> <gate> <wire> -> <wire>`"))?;
+        if arrow != "->" {
+            return Err(line.error(arrow, "expected \"->\""));
+        }
+
+        Ok(Operation {
+            left: left.to_string(),
+            right: right.to_string(),
+            target: target.to_string(),
+            gate: match gate {
+                "OR" => Gate::OR,
+                "XOR" => Gate::XOR,
+                "AND" => Gate::AND,
+                _ => return Err(line.error(gate, "unknown gate")),
+            },
         })
-        .collect_vec();
+    })?;
 
-    Input { values, operations }
+    Ok(Input { values, operations })
 }
 
 #[cfg(test)]
@@ -740,7 +739,10 @@ mod tests {
 
     #[test]
     fn test_part1_1() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let input = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result1 = part1(&input);
 
         assert_eq!(result1, 4);
@@ -748,7 +750,10 @@ mod tests {
 
     #[test]
     fn test_part1_2() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test2.txt"));
+        let input = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test2.txt"),
+        ))
+        .unwrap();
         let result1 = part1(&input);
 
         assert_eq!(result1, 2024);
@@ -756,7 +761,10 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test3.txt"));
+        let input = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test3.txt"),
+        ))
+        .unwrap();
         let result2 = part2_better(&input);
 
         assert_eq!(result2, "z00,z01,z02,z05");
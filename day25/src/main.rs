@@ -1,9 +1,6 @@
+use advent::grid::Grid;
+use advent::parse::{ParseError, Parser as AdventParser};
 use clap::Parser;
-use itertools::Itertools;
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -28,7 +25,7 @@ fn main() {
         args.data_file
     };
 
-    let input = parse(&data_file);
+    let input = AdventParser::parse_or_exit(&data_file, parse);
 
     let result1 = part1(&input);
     println!("Part1: {}", result1);
@@ -55,67 +52,36 @@ fn part1(input: &Input) -> i64 {
     matches
 }
 
-fn parse(file: &str) -> Input {
-    let file = File::open(file).expect("Failed to open file");
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .map(|line| line.expect("Failed to read line"))
-        .collect();
-
-    let keys_and_locks = lines
-        .iter()
-        .chunks(8)
-        .into_iter()
-        .map(|c| c.filter(|l| !l.is_empty()).collect_vec())
-        .collect_vec();
-
-    let keys = keys_and_locks
-        .iter()
-        .filter(|l| l[0].chars().next().unwrap() == '.')
-        .map(|key| {
-            [
-                key_height(key, 0),
-                key_height(key, 1),
-                key_height(key, 2),
-                key_height(key, 3),
-                key_height(key, 4),
-            ]
-        })
-        .collect_vec();
-
-    let locks = keys_and_locks
-        .iter()
-        .filter(|l| l[0].chars().next().unwrap() != '.')
-        .map(|key| {
-            [
-                lock_height(key, 0),
-                lock_height(key, 1),
-                lock_height(key, 2),
-                lock_height(key, 3),
-                lock_height(key, 4),
-            ]
-        })
-        .collect_vec();
-
-    Input { keys, locks }
-}
+fn parse(parser: &AdventParser) -> Result<Input, ParseError> {
+    let mut keys = Vec::new();
+    let mut locks = Vec::new();
+
+    for section in parser.try_sections()? {
+        let schematic = section.as_grid()?;
+        if schematic.width() != 5 || schematic.height() != 7 {
+            return Err(ParseError::new(
+                parser.source(),
+                format!("expected a 5x7 schematic at line {}", section.first_line()),
+            ));
+        }
+
+        // Keys have an empty top row, locks a filled one.
+        if schematic.cells()[0] == '.' {
+            keys.push(heights(&schematic));
+        } else {
+            locks.push(heights(&schematic));
+        }
+    }
 
-fn key_height(key: &Vec<&String>, index: usize) -> i64 {
-    6 - (key
-        .iter()
-        .find_position(|l| l.chars().nth(index).unwrap() == '#')
-        .unwrap()
-        .0 as i64)
+    Ok(Input { keys, locks })
 }
 
-fn lock_height(lock: &Vec<&String>, index: usize) -> i64 {
-    6 - (lock
-        .iter()
-        .rev()
-        .find_position(|l| l.chars().nth(index).unwrap() == '#')
-        .unwrap()
-        .0 as i64)
+fn heights(schematic: &Grid<char>) -> [i64; 5] {
+    std::array::from_fn(|x| {
+        schematic
+            .column(x)
+            .map_or(0, |column| column.filter(|c| **c == '#').count() as i64 - 1)
+    })
 }
 
 #[cfg(test)]
@@ -124,7 +90,10 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let input = parse(&AdventParser::new(
+            &(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"),
+        ))
+        .unwrap();
         let result1 = part1(&input);
 
         assert_eq!(result1, 3);
@@ -1,9 +1,5 @@
+use advent::parse::{ParseError, Parser as AdventParser};
 use clap::Parser;
-use itertools::Itertools;
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -33,7 +29,7 @@ fn main() {
         args.data_file
     };
 
-    let input = parse(&data_file);
+    let input = AdventParser::parse_or_exit(&data_file, parse);
 
     let result1 = part1(&input);
     println!("Part1: {}", result1);
@@ -49,48 +45,18 @@ fn part2(input: &Input) -> i64 {
     0
 }
 
-fn parse(file: &str) -> Input {
-    let file = File::open(file).expect("Failed to open file");
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .map(|line| line.expect("Failed to read line"))
-        .collect();
-
-    Input {
-        values: lines
-            .iter()
-            .map(|line| {
-                line.split_ascii_whitespace()
-                    .map(|v| v.parse::<i64>().unwrap())
-                    .collect()
-            })
-            .collect_vec(),
-    }
+fn parse(parser: &AdventParser) -> Result<Input, ParseError> {
+    Ok(Input {
+        values: parser.try_parse_lines(|line| line.whitespace_fields::<i64>())?,
+    })
 
     /*
      * Alternative implementations:
      */
 
-    // Two sections separated by a newline
-    // Input {
-    //     first: lines
-    //         .iter()
-    //         .take_while(|line| !line.is_empty())
-    //         .map(|line| line.split_once('|').unwrap())
-    //         .map(|(a, b)| (a.parse::<i64>().unwrap(), b.parse::<i64>().unwrap()))
-    //         .collect_vec(),
-    //     second: lines
-    //         .iter()
-    //         .skip_while(|line| !line.is_empty())
-    //         .filter(|line| !line.is_empty())
-    //         .map(|line| {
-    //             line.split(',')
-    //                 .map(|page| page.parse::<i64>().unwrap())
-    //                 .collect_vec()
-    //         })
-    //         .collect_vec(),
-    // }
+    // Sections separated by blank lines
+    // let mut sections = parser.try_sections()?;
+    // let rules = sections.next().unwrap().parse_lines(|line| line.fields::<i64>("|"))?;
 
     // Creates a HashMap<char, Vec<Position>>
     // let map_limits = Position {
@@ -130,9 +96,11 @@ fn parse(file: &str) -> Input {
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "";
+
     #[test]
     fn test_part1() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let input = parse(&AdventParser::from_str(EXAMPLE)).unwrap();
         let result1 = part1(&input);
 
         assert_eq!(result1, 0);
@@ -140,7 +108,7 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        let input = parse(&(env!("CARGO_MANIFEST_DIR").to_owned() + "/src/test1.txt"));
+        let input = parse(&AdventParser::from_str(EXAMPLE)).unwrap();
         let result2 = part2(&input);
 
         assert_eq!(result2, 0);